@@ -0,0 +1,59 @@
+//! Ingestion throughput of the `Engine`.
+//!
+//! Compare the default hasher against `--features fast-hash`.
+
+use std::fmt::Write;
+
+use std::path::PathBuf;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use koncord::Engine;
+
+fn ingest(c: &mut Criterion) {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/100k_transactions.csv");
+    let records = std::fs::read(&records_path).unwrap();
+
+    let mut group = c.benchmark_group("ingest");
+    group.throughput(Throughput::Bytes(records.len() as u64));
+    group.sample_size(10);
+    group.bench_function("100k_transactions", |b| {
+        b.iter(|| {
+            let mut engine = Engine::new();
+            engine
+                .process(koncord::reader_builder().from_reader(records.as_slice()))
+                .unwrap();
+            engine
+        })
+    });
+    group.finish();
+}
+
+// Per-record cost of deposits and withdrawals, the bulk of most streams.
+fn deposits_withdrawals(c: &mut Criterion) {
+    const RECORDS: u32 = 100_000;
+
+    let mut records = String::from("type,client,tx,amount\n");
+    for tx in 0..RECORDS {
+        let kind = if tx % 4 == 3 { "withdrawal" } else { "deposit" };
+        writeln!(records, "{kind},{},{tx},1.5", tx % 1000).unwrap();
+    }
+
+    let mut group = c.benchmark_group("ingest");
+    group.throughput(Throughput::Elements(u64::from(RECORDS)));
+    group.sample_size(10);
+    group.bench_function("deposits_withdrawals", |b| {
+        b.iter(|| {
+            let mut engine = Engine::new();
+            engine
+                .process(koncord::reader_builder().from_reader(records.as_bytes()))
+                .unwrap();
+            engine
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, ingest, deposits_withdrawals);
+criterion_main!(benches);
@@ -5,265 +5,1601 @@
 //! implements all operations on accounts.
 
 use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
 
-use rust_decimal::Decimal;
-use serde::ser::{Serialize, SerializeStruct, Serializer};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::ser::{Error, Serialize, SerializeStruct, Serializer};
+use serde::Deserialize;
 
-/// The number of digits to the right of the decimal point.
-///
-/// A scale of four places past the decimal for all values.
-const SCALE: u32 = 4;
+use crate::error::KoncordError;
+use crate::money::Money;
+use crate::transaction::TransactionKind;
+use crate::DEFAULT_SCALE;
 
 /// A client represented by a Client ID and the associated account.
 ///
-/// `Client` also implements `Serialize` directly to the output format.
-#[derive(Debug, Eq, PartialEq)]
-pub struct Client {
+/// `Client` also implements `Serialize` directly to the output format. The
+/// balance is kept in `M`, see `Money`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Client<M: Money = Decimal> {
     id: u16,
-    account: Account,
+    account: Account<M>,
 }
 
 impl Client {
     /// Create a new `Client` with `id` and `0` balance.
     pub fn new(id: u16) -> Self {
-        Client {
-            id,
-            account: Account::new(),
-        }
+        Client::with_scale(id, DEFAULT_SCALE)
     }
 
-    /// Returns a mutable reference to the `Client`s `Account`.
+    /// Create a new `Client` with `id` and `0` balance at `scale` decimal
+    /// places.
+    pub fn with_scale(id: u16, scale: u32) -> Self {
+        Self::zeroed(id, scale)
+    }
+}
+
+impl<M: Money> Client<M> {
+    // Create a client with `id` and `0` balance at `scale` decimal places,
+    // for any `Money`.
+    pub(crate) fn zeroed(id: u16, scale: u32) -> Self {
+        let mut account = Account::new();
+        account.inner = AccountInner::Open {
+            balance: Balance::with_scale(scale),
+        };
+        Client { id, account }
+    }
+
+    /// Returns the Client ID.
     pub fn id(&self) -> u16 {
         self.id
     }
 
+    /// Returns a reference to the `Client`s `Account`.
+    pub fn account(&self) -> &Account<M> {
+        &self.account
+    }
+
+    /// Returns `true` if the `Client`s `Account` has been locked by a
+    /// chargeback.
+    pub fn is_locked(&self) -> bool {
+        self.account.is_locked()
+    }
+
+    /// Returns the Transaction ID of the chargeback that locked the `Client`s
+    /// `Account`.
+    pub fn frozen_at(&self) -> Option<u32> {
+        self.account.frozen_at()
+    }
+
+    /// Returns the balance of the `Client`s `Account`.
+    pub fn balance(&self) -> &Balance<M> {
+        self.account.balance()
+    }
+
+    /// Returns the number of transactions applied to the account by type.
+    ///
+    /// Types without any applied transactions are left out. Coalesced
+    /// deposits count each deposit.
+    pub fn activity_summary(&self) -> HashMap<TransactionKind, u32> {
+        let activity = &self.account.activity;
+        [
+            (TransactionKind::Deposit, activity.deposits),
+            (TransactionKind::Withdrawal, activity.withdrawals),
+            (TransactionKind::Adjustment, activity.adjustments),
+            (TransactionKind::Hold, activity.holds),
+            (TransactionKind::Release, activity.releases),
+            (TransactionKind::Fee, activity.fees),
+            (TransactionKind::Dispute, activity.disputes),
+            (TransactionKind::Resolve, activity.resolves),
+            (TransactionKind::Chargeback, activity.chargebacks),
+            (TransactionKind::Reversal, activity.reversals),
+            (TransactionKind::Correction, activity.corrections),
+        ]
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(kind, count)| (kind, u32::try_from(count).unwrap_or(u32::MAX)))
+        .collect()
+    }
+
+    /// Returns the number of disputes held open on the account.
+    ///
+    /// Partially resolved disputes remain open, holds are not disputes.
+    pub fn open_dispute_count(&self) -> usize {
+        self.account.open_disputes
+    }
+
+    /// Returns `true` if both clients have the same balances and lock state.
+    ///
+    /// Balances are compared numerically so `0.0000` equals `0.00`, Client
+    /// IDs are not compared.
+    pub fn balances_equal(&self, other: &Client<M>) -> bool {
+        let (account, other) = (&self.account, &other.account);
+        account.available() == other.available()
+            && account.held() == other.held()
+            && account.total() == other.total()
+            && account.is_locked() == other.is_locked()
+    }
+
+    /// Merge `other` into this client for account consolidation.
+    ///
+    /// Available, held and total funds of `other` are added to this client's
+    /// account which is locked if either account was locked, the funds held
+    /// by each transaction are kept. Fails leaving this client as is with
+    /// `KoncordError::MergeConflict` if both hold funds under the same
+    /// Transaction ID, or `KoncordError::MergeOverflow` if the funds overflow.
+    pub fn merge_into(&mut self, other: Client<M>) -> Result<(), KoncordError> {
+        self.account.merge(other.account)
+    }
+
+    /// Ordering for reports, by total funds descending then Client ID.
+    ///
+    /// The canonical `Ord` orders by Client ID alone, use this with
+    /// `sort_by` to group clients by balance.
+    pub fn cmp_report(&self, other: &Self) -> Ordering {
+        other
+            .account
+            .total()
+            .cmp(&self.account.total())
+            .then(self.id.cmp(&other.id))
+    }
+
+    /// Returns a reference to the `Client`s `Account`.
+    pub fn get(&self) -> &Account<M> {
+        &self.account
+    }
+
     /// Returns a mutable reference to the `Client`s `Account`.
-    pub fn get_mut(&mut self) -> &mut Account {
+    pub fn get_mut(&mut self) -> &mut Account<M> {
         &mut self.account
     }
 }
 
-impl Ord for Client {
+impl<M: Money> Ord for Client<M> {
     fn cmp(&self, other: &Self) -> Ordering {
         self.id.cmp(&other.id)
     }
 }
 
-impl PartialOrd for Client {
+impl<M: Money> PartialOrd for Client<M> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.id.cmp(&other.id))
+        Some(self.cmp(other))
+    }
+}
+
+/// The state of a `Client` as written to the output.
+///
+/// Snapshots deserialize from the output rows so a previous run can be
+/// resumed, see `load_clients`.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+pub struct ClientSnapshot {
+    /// Client ID.
+    pub client: u16,
+    /// Funds available for withdrawal.
+    pub available: Decimal,
+    /// Funds held by open disputes.
+    pub held: Decimal,
+    /// Total funds, available and held.
+    pub total: Decimal,
+    /// The account has been locked by a chargeback.
+    pub locked: bool,
+    /// The account has been flagged by a chargeback.
+    #[serde(default)]
+    pub flagged: bool,
+}
+
+impl TryFrom<ClientSnapshot> for Client {
+    type Error = KoncordError;
+
+    fn try_from(snapshot: ClientSnapshot) -> Result<Self, Self::Error> {
+        if snapshot.available.checked_add(snapshot.held) != Some(snapshot.total) {
+            return Err(KoncordError::InvalidSnapshot {
+                client: snapshot.client,
+            });
+        }
+
+        Ok(Client::from_snapshot_unchecked(snapshot))
+    }
+}
+
+impl Client {
+    // Build a client from `snapshot` without checking its total.
+    pub(crate) fn from_snapshot_unchecked(snapshot: ClientSnapshot) -> Client {
+        let balance = Balance {
+            available: snapshot.available,
+            held: snapshot.held,
+            total: snapshot.total,
+        };
+        let inner = if snapshot.locked {
+            AccountInner::Frozen { balance }
+        } else if snapshot.flagged {
+            AccountInner::Flagged { balance }
+        } else {
+            AccountInner::Open { balance }
+        };
+
+        Client {
+            id: snapshot.client,
+            account: Account {
+                inner,
+                held_by_tx: HashMap::new(),
+                holds: HashMap::new(),
+                open_disputes: 0,
+                activity: Activity::default(),
+                frozen_at_tx: None,
+            },
+        }
+    }
+}
+
+/// Clients equal snapshots of the same Client ID, balances and lock state.
+///
+/// Balances compare numerically so `1.5` equals `1.5000`, the flagged state
+/// is not compared.
+impl PartialEq<ClientSnapshot> for Client {
+    fn eq(&self, snapshot: &ClientSnapshot) -> bool {
+        let balance = self.balance();
+        self.id == snapshot.client
+            && balance.available == snapshot.available
+            && balance.held == snapshot.held
+            && balance.total == snapshot.total
+            && self.is_locked() == snapshot.locked
+    }
+}
+
+impl PartialEq<Client> for ClientSnapshot {
+    fn eq(&self, client: &Client) -> bool {
+        client == self
+    }
+}
+
+impl From<&Client> for ClientSnapshot {
+    fn from(client: &Client) -> Self {
+        let account = &client.account;
+        ClientSnapshot {
+            client: client.id,
+            available: account.available(),
+            held: account.held(),
+            total: account.total(),
+            locked: account.is_locked(),
+            flagged: account.is_flagged(),
+        }
+    }
+}
+
+/// Build clients keyed by Client ID from `snapshots`.
+///
+/// Fails on the first snapshot whose total is not its available plus held
+/// funds. Held funds are restored without their breakdown by transaction.
+pub fn load_clients<I>(snapshots: I) -> Result<HashMap<u16, Client>, KoncordError>
+where
+    I: IntoIterator<Item = ClientSnapshot>,
+{
+    snapshots
+        .into_iter()
+        .map(|snapshot| Ok((snapshot.client, Client::try_from(snapshot)?)))
+        .collect()
+}
+
+/// Options controlling how a `Client` is serialized.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct OutputOptions {
+    /// Output balances as integer counts of minor units at this scale, for
+    /// example cents with a scale of `2`.
+    pub minor_units: Option<u32>,
+    /// Output balances rounded or padded to this many decimal places.
+    /// Ignored when `minor_units` is set.
+    pub scale: Option<u32>,
+    /// Output balances zero-padded to at least this many characters, for
+    /// example `000000001.5000` at a width of `14`, for fixed-width
+    /// consumers. Ignored when `minor_units` is set.
+    pub fixed_width: Option<usize>,
+    /// Output a `flagged` column after `locked`.
+    pub flagged: bool,
+    /// Output a trailing `computed_total` column of available plus held funds
+    /// so `total` can be verified independently, any mismatch between the two
+    /// columns flags a corrupt balance.
+    pub computed_total: bool,
+    /// Output only clients with records in this run, leaving out clients
+    /// carried over from a previous run without any.
+    pub only_active: bool,
+    /// How locked accounts with a zero balance are output.
+    pub locked_zero_balance: LockedZeroBalance,
+    /// Output nothing rather than the header row when there are no clients,
+    /// such as for an empty or header-only input.
+    pub omit_empty_header: bool,
+}
+
+impl OutputOptions {
+    // Returns the columns of a `ClientRow` serialized with these options.
+    pub(crate) fn headers(&self) -> Vec<&'static str> {
+        let mut headers = vec!["client", "available", "held", "total", "locked"];
+        if self.flagged {
+            headers.push("flagged");
+        }
+        if self.computed_total {
+            headers.push("computed_total");
+        }
+        headers
+    }
+}
+
+/// How locked accounts with a zero balance are output.
+///
+/// A locked account holds no funds once fully charged back, some consumers
+/// treat these as closed rather than locked.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum LockedZeroBalance {
+    /// Output `true` in the `locked` column as for any locked account.
+    #[default]
+    Locked,
+    /// Output `closed` in the `locked` column.
+    Closed,
+    /// Leave the account out of the output.
+    Omit,
+}
+
+/// A `Client` serialized according to `OutputOptions`.
+#[derive(Debug)]
+pub struct ClientRow<'a, M: Money = Decimal> {
+    client: &'a Client<M>,
+    options: &'a OutputOptions,
+}
+
+impl<M: Money> Client<M> {
+    /// Returns this client as an output row serialized with `options`.
+    pub fn row<'a>(&'a self, options: &'a OutputOptions) -> ClientRow<'a, M> {
+        ClientRow {
+            client: self,
+            options,
+        }
     }
 }
 
 // Required due to rust-csv issue "Support serializing of maps #98"
 //
 // See: https://github.com/BurntSushi/rust-csv/issues/98
-impl Serialize for Client {
+impl<M: Money> Serialize for ClientRow<'_, M> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let (locked, balance) = match &self.account.inner {
-            AccountInner::Open { balance } => ("false", balance),
+        let (locked, balance) = match &self.client.account.inner {
+            AccountInner::Open { balance } | AccountInner::Flagged { balance } => {
+                ("false", balance)
+            }
+            AccountInner::Frozen { balance }
+                if balance.is_zero()
+                    && self.options.locked_zero_balance == LockedZeroBalance::Closed =>
+            {
+                ("closed", balance)
+            }
             AccountInner::Frozen { balance } => ("true", balance),
         };
 
         let mut row = serializer.serialize_struct("Client", 4)?;
-        row.serialize_field("client", &self.id)?;
-        row.serialize_field("available", &balance.available)?;
-        row.serialize_field("held", &balance.held)?;
-        row.serialize_field("total", &balance.total)?;
+        row.serialize_field("client", &self.client.id)?;
+        let computed_total = if let Some(scale) = self.options.minor_units {
+            let (available, held, total) = balance
+                .to_minor_units(scale)
+                .ok_or_else(|| S::Error::custom("balance overflows minor units"))?;
+            row.serialize_field("available", &available)?;
+            row.serialize_field("held", &held)?;
+            row.serialize_field("total", &total)?;
+            self.options
+                .computed_total
+                .then(|| {
+                    available
+                        .checked_add(held)
+                        .map(|computed_total| computed_total.to_string())
+                        .ok_or_else(|| S::Error::custom("computed total overflows minor units"))
+                })
+                .transpose()?
+        } else {
+            let scaled = |mut value: Decimal| {
+                if let Some(scale) = self.options.scale {
+                    value.rescale(scale);
+                }
+                match self.options.fixed_width {
+                    Some(width) => zero_padded(value, width),
+                    None => value.to_string(),
+                }
+            };
+            row.serialize_field("available", &scaled(balance.available()))?;
+            row.serialize_field("held", &scaled(balance.held()))?;
+            row.serialize_field("total", &scaled(balance.total()))?;
+            // Loaded or merged balances may not be those of the engine, so
+            // available plus held funds may overflow.
+            self.options
+                .computed_total
+                .then(|| {
+                    balance
+                        .available()
+                        .checked_add(balance.held())
+                        .map(scaled)
+                        .ok_or_else(|| S::Error::custom("computed total overflows"))
+                })
+                .transpose()?
+        };
         row.serialize_field("locked", locked)?;
+        if self.options.flagged {
+            let flagged = if self.client.account.is_flagged() {
+                "true"
+            } else {
+                "false"
+            };
+            row.serialize_field("flagged", flagged)?;
+        }
+        if let Some(computed_total) = computed_total {
+            row.serialize_field("computed_total", &computed_total)?;
+        }
         row.end()
     }
 }
 
+// Format `value` zero-padded to at least `width` characters, any sign leading
+// the padding.
+fn zero_padded(value: Decimal, width: usize) -> String {
+    format!("{value:0width$}")
+}
+
+impl<M: Money> Serialize for Client<M> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.row(&OutputOptions::default()).serialize(serializer)
+    }
+}
+
+/// Outcome of applying an operation to an `Account`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OpResult {
+    /// The operation was applied to the account balance.
+    Applied,
+    /// The account is locked, the operation was ignored.
+    SkippedLocked,
+    /// The amount was not positive, the operation was ignored.
+    SkippedInvalidAmount,
+    /// The account lacks the available funds, the operation was ignored.
+    SkippedInsufficientFunds,
+    /// The account lacks the available funds but its held funds would have
+    /// covered the withdrawal, the operation was ignored.
+    SkippedFundsHeld,
+    /// The chargeback exceeded the funds held by its dispute or the total
+    /// funds and was applied for those funds only, see
+    /// `ExcessChargebackPolicy`.
+    Clamped,
+    /// The chargeback exceeded the total funds, the operation was ignored.
+    SkippedExceedsTotal,
+    /// The transaction type is unknown, the operation was ignored.
+    SkippedUnknownKind,
+    /// The operation cannot be undone, such as a chargeback, and was
+    /// ignored.
+    SkippedIrreversible,
+    /// The hold or release refers to a transaction with an open dispute, the
+    /// operation was ignored.
+    SkippedDisputed,
+    /// The operation would overflow the funds of the balance, the operation
+    /// was ignored.
+    SkippedOverflow,
+}
+
+impl OpResult {
+    /// Returns the outcome in snake case, such as `insufficient_funds`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OpResult::Applied => "applied",
+            OpResult::SkippedLocked => "locked",
+            OpResult::SkippedInvalidAmount => "invalid_amount",
+            OpResult::SkippedInsufficientFunds => "insufficient_funds",
+            OpResult::SkippedFundsHeld => "funds_held",
+            OpResult::Clamped => "clamped",
+            OpResult::SkippedExceedsTotal => "exceeds_total",
+            OpResult::SkippedUnknownKind => "unknown_kind",
+            OpResult::SkippedIrreversible => "irreversible",
+            OpResult::SkippedDisputed => "disputed",
+            OpResult::SkippedOverflow => "overflow",
+        }
+    }
+}
+
+/// What happens to an account after a chargeback.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum ChargebackPolicy {
+    /// Lock the account, refusing all further transactions.
+    #[default]
+    Freeze,
+    /// Flag the account for review, transactions still apply.
+    Flag,
+}
+
+/// What happens to a chargeback exceeding the total funds of an account.
+///
+/// Disputing a deposit after funds were withdrawn can hold more than the total
+/// funds, charging back the full amount would leave a negative total.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum ExcessChargebackPolicy {
+    /// Charge back the full amount leaving a negative total.
+    #[default]
+    Allow,
+    /// Charge back no more than the total funds.
+    Clamp,
+    /// Refuse the chargeback, the funds remain held.
+    Refuse,
+}
+
+/// What happens to a dispute exceeding the available funds of an account.
+///
+/// Disputing a deposit after its funds were withdrawn holds more than is
+/// available.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum DisputePolicy {
+    /// Hold the full amount leaving negative available funds.
+    #[default]
+    Overdraft,
+    /// Refuse the dispute with `OpResult::SkippedInsufficientFunds`, so
+    /// only deposits whose funds are still fully available can be disputed.
+    Refuse,
+}
+
+/// What happens to a fee exceeding the available funds of an account.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum FeePolicy {
+    /// Refuse the fee with `OpResult::SkippedInsufficientFunds`.
+    #[default]
+    Refuse,
+    /// Charge the full fee leaving negative available funds.
+    Overdraft,
+}
+
+/// How deposit and withdrawal amounts with more decimal places than the
+/// balance scale are rounded.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Rounding {
+    /// Apply amounts as recorded, balances may carry sub-minor-unit dust.
+    #[default]
+    Exact,
+    /// Round to the nearest, half to even.
+    HalfEven,
+    /// Round to the nearest, half away from zero.
+    HalfUp,
+    /// Truncate towards zero.
+    Down,
+}
+
+impl Rounding {
+    /// Returns `amount` rounded to `scale` decimal places.
+    pub fn round(self, amount: Decimal, scale: u32) -> Decimal {
+        let strategy = match self {
+            Rounding::Exact => return amount,
+            Rounding::HalfEven => RoundingStrategy::MidpointNearestEven,
+            Rounding::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            Rounding::Down => RoundingStrategy::ToZero,
+        };
+        amount.round_dp_with_strategy(scale, strategy)
+    }
+}
+
+/// Policies applied by account operations.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct AccountPolicy {
+    /// What happens to an account after a chargeback.
+    pub chargeback: ChargebackPolicy,
+    /// What happens to a chargeback exceeding the total funds.
+    pub excess_chargeback: ExcessChargebackPolicy,
+    /// What happens to a dispute exceeding the available funds.
+    pub dispute: DisputePolicy,
+    /// What happens to a fee exceeding the available funds.
+    pub fee: FeePolicy,
+    /// Clamp balances at zero after every operation so they never go
+    /// negative, guarding against corrupt upstream data.
+    pub saturating: bool,
+    /// How deposit and withdrawal amounts are rounded before being applied.
+    pub rounding: Rounding,
+    /// Decimal places amounts are rounded to, `DEFAULT_SCALE` if `None`.
+    pub scale: Option<u32>,
+}
+
+/// Counts and sums of the transactions applied to an account.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Activity {
+    /// Number of deposits applied.
+    pub deposits: usize,
+    /// Number of withdrawals applied.
+    pub withdrawals: usize,
+    /// Number of adjustments applied.
+    pub adjustments: usize,
+    /// Number of holds applied.
+    pub holds: usize,
+    /// Number of releases applied.
+    pub releases: usize,
+    /// Number of fees applied.
+    pub fees: usize,
+    /// Number of disputes applied.
+    pub disputes: usize,
+    /// Number of resolves applied.
+    pub resolves: usize,
+    /// Number of chargebacks applied, including clamped chargebacks.
+    pub chargebacks: usize,
+    /// Number of reversals applied.
+    pub reversals: usize,
+    /// Number of corrections applied.
+    pub corrections: usize,
+    /// Sum of deposits applied, saturating at `Decimal::MAX`.
+    pub deposited: Decimal,
+    /// Sum of withdrawals applied, saturating at `Decimal::MAX`.
+    pub withdrawn: Decimal,
+    /// Sum of chargebacks exceeding the funds held by their dispute, which
+    /// were clamped to the funds held, saturating at `Decimal::MAX`.
+    pub chargeback_shortfall: Decimal,
+}
+
 /// Client account.
 ///
 /// Accounts have two primary states `Open` and `Frozen`. When accounts are
 /// `Open` nearly all transactions are permitted with the exception of
 /// withdrawals due to insufficient funds and any transaction with a negative
 /// amount. All transactions are disallowed when the account is locked.
-#[derive(Debug, Eq, PartialEq)]
-pub struct Account {
-    inner: AccountInner,
+/// Under `ChargebackPolicy::Flag` a chargeback leaves the account `Flagged`
+/// instead, which permits transactions as when `Open`.
+///
+/// Accounts compare equal by balances and lock state, the held breakdown is
+/// not compared.
+#[derive(Debug, Clone)]
+pub struct Account<M: Money = Decimal> {
+    inner: AccountInner<M>,
+    // Funds held by open disputes keyed by the disputed Transaction ID.
+    held_by_tx: HashMap<u32, Decimal>,
+    // Funds held by administrative holds keyed by the hold Transaction ID.
+    holds: HashMap<u32, Decimal>,
+    open_disputes: usize,
+    activity: Activity,
+    frozen_at_tx: Option<u32>,
+}
+
+impl<M: Money> PartialEq for Account<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
 }
 
-impl Account {
+impl<M: Money> Eq for Account<M> {}
+
+impl<M: Money> Account<M> {
     fn new() -> Self {
         Self {
             inner: AccountInner::new(),
+            held_by_tx: HashMap::new(),
+            holds: HashMap::new(),
+            open_disputes: 0,
+            activity: Activity::default(),
+            frozen_at_tx: None,
         }
     }
 
-    /// Increase the available and total funds of the client account by amount.
+    /// Returns the funds available for withdrawal.
+    pub fn available(&self) -> Decimal {
+        self.balance().available()
+    }
+
+    /// Returns the funds held by open disputes.
+    pub fn held(&self) -> Decimal {
+        self.balance().held()
+    }
+
+    /// Returns the total funds, available and held.
+    pub fn total(&self) -> Decimal {
+        self.balance().total()
+    }
+
+    /// Returns `true` if the account has been locked by a chargeback.
+    pub fn is_locked(&self) -> bool {
+        matches!(self.inner, AccountInner::Frozen { .. })
+    }
+
+    /// Returns `true` if the account has been flagged by a chargeback.
+    pub fn is_flagged(&self) -> bool {
+        matches!(self.inner, AccountInner::Flagged { .. })
+    }
+
+    /// Returns the scale of the available, held and total funds.
     ///
-    /// Only fails when the account is locked or amount is negative.
-    pub(crate) fn deposit(&mut self, amount: Decimal) {
-        match &mut self.inner {
-            AccountInner::Open { balance } => balance.deposit(amount),
-            _ => (),
-        }
+    /// Balances keep the scale of the amounts applied to them, this helps
+    /// diagnose inconsistent scales in the output.
+    pub fn scales(&self) -> (u32, u32, u32) {
+        let balance = self.balance();
+        (
+            balance.available_scale(),
+            balance.held_scale(),
+            balance.total_scale(),
+        )
     }
 
-    /// Decrease the available and total funds of the client account by amount.
+    /// Returns the Transaction ID of the chargeback that locked the account.
+    pub fn frozen_at(&self) -> Option<u32> {
+        self.frozen_at_tx
+    }
+
+    /// Returns the counts and sums of transactions applied to the account.
+    pub fn activity(&self) -> &Activity {
+        &self.activity
+    }
+
+    /// Returns the sum of deposits minus the sum of withdrawals applied.
     ///
-    /// Fails if account is locked, the account does not have sufficient
-    /// available funds, or if the amount is negative.
-    pub fn withdraw(&mut self, amount: Decimal) {
-        match &mut self.inner {
-            AccountInner::Open { balance } => {
-                balance.withdraw(amount);
-            }
-            _ => (),
-        }
+    /// Unlike the balance the net position is not changed by disputes,
+    /// resolves or chargebacks, for reconciling against settled funds. Only
+    /// exact while neither sum in `Activity` has saturated.
+    pub fn net_position(&self) -> Decimal {
+        self.activity
+            .deposited
+            .saturating_sub(self.activity.withdrawn)
     }
 
-    /// Associated funds moved to held.
+    /// Returns the funds held by open disputes keyed by the disputed
+    /// Transaction ID.
     ///
-    /// Available funds decreased by amount, held funds increased by amount,
-    /// total funds remain the same. Fails if account is locked or amount is
-    /// negative.
-    pub fn dispute(&mut self, amount: Decimal) {
-        match &mut self.inner {
-            AccountInner::Open { balance } => balance.dispute(amount),
-            _ => (),
+    /// Together with the amounts of `holds` they sum to `held`.
+    pub fn held_breakdown(&self) -> &HashMap<u32, Decimal> {
+        &self.held_by_tx
+    }
+
+    /// Returns the funds held by administrative holds keyed by the hold
+    /// Transaction ID.
+    pub fn holds(&self) -> &HashMap<u32, Decimal> {
+        &self.holds
+    }
+
+    /// Returns the available, held and total funds.
+    pub fn balance(&self) -> &Balance<M> {
+        match &self.inner {
+            AccountInner::Open { balance }
+            | AccountInner::Flagged { balance }
+            | AccountInner::Frozen { balance } => balance,
+        }
+    }
+
+    // Add the balance of `other` to this account, locking or flagging it if
+    // either account is.
+    fn merge(&mut self, other: Account<M>) -> Result<(), KoncordError> {
+        let locked = self.is_locked() || other.is_locked();
+        let flagged = self.is_flagged() || other.is_flagged();
+        // Funds held by transaction are keyed by Transaction ID alone, the
+        // same ID held by both accounts cannot be told apart once merged.
+        let conflict = other
+            .held_by_tx
+            .keys()
+            .chain(other.holds.keys())
+            .filter(|tx| self.held_by_tx.contains_key(tx) || self.holds.contains_key(tx))
+            .min();
+        if let Some(tx) = conflict {
+            return Err(KoncordError::MergeConflict { tx: *tx });
         }
+        let Some(balance) = self.balance().checked_add(other.balance()) else {
+            return Err(KoncordError::MergeOverflow);
+        };
+
+        self.inner = if locked {
+            AccountInner::Frozen { balance }
+        } else if flagged {
+            AccountInner::Flagged { balance }
+        } else {
+            AccountInner::Open { balance }
+        };
+        self.held_by_tx.extend(other.held_by_tx);
+        self.holds.extend(other.holds);
+        self.open_disputes += other.open_disputes;
+        self.activity.deposits += other.activity.deposits;
+        self.activity.withdrawals += other.activity.withdrawals;
+        self.activity.adjustments += other.activity.adjustments;
+        self.activity.holds += other.activity.holds;
+        self.activity.releases += other.activity.releases;
+        self.activity.fees += other.activity.fees;
+        self.activity.disputes += other.activity.disputes;
+        self.activity.resolves += other.activity.resolves;
+        self.activity.chargebacks += other.activity.chargebacks;
+        self.activity.reversals += other.activity.reversals;
+        self.activity.corrections += other.activity.corrections;
+        self.activity.deposited = self
+            .activity
+            .deposited
+            .saturating_add(other.activity.deposited);
+        self.activity.withdrawn = self
+            .activity
+            .withdrawn
+            .saturating_add(other.activity.withdrawn);
+        self.activity.chargeback_shortfall = self
+            .activity
+            .chargeback_shortfall
+            .saturating_add(other.activity.chargeback_shortfall);
+        self.frozen_at_tx = self.frozen_at_tx.or(other.frozen_at_tx);
+        Ok(())
     }
 
-    /// Resolution to a dispute, releases held funds.
+    /// Apply the operation for transaction `kind` with `amount`.
     ///
-    /// Held funds decreased by amount, available funds increased by amount,
-    /// total funds remain the same. Fails if account is locked or amount is
-    /// negative.
-    pub fn resolve(&mut self, amount: Decimal) {
-        match &mut self.inner {
-            AccountInner::Open { balance } => balance.resolve(amount),
-            _ => (),
+    /// Dispatches to the matching account operation, `tx` is the disputed
+    /// Transaction ID for disputes, resolves and chargebacks and the held
+    /// Transaction ID for holds and releases.
+    pub fn apply(&mut self, kind: &TransactionKind, tx: u32, amount: Decimal) -> OpResult {
+        match kind {
+            TransactionKind::Deposit => self.deposit(amount),
+            TransactionKind::Withdrawal => self.withdraw(amount),
+            TransactionKind::Adjustment => self.adjust(amount),
+            TransactionKind::Hold => self.hold(tx, amount),
+            TransactionKind::Release => self.release(tx, amount),
+            TransactionKind::Fee => self.fee(amount),
+            TransactionKind::Dispute => self.dispute(tx, amount),
+            TransactionKind::Resolve => self.resolve(tx, amount),
+            TransactionKind::Chargeback => self.chargeback(tx, amount),
+            TransactionKind::Reversal => self.reverse(amount),
+            TransactionKind::Correction => self.correct(amount),
+            TransactionKind::Unknown(_) => OpResult::SkippedUnknownKind,
         }
     }
 
-    /// Final state of a dispute and represents the client reversing a transaction.
+    /// Undo the operation for transaction `kind` with `amount` as applied by
+    /// `apply`.
     ///
-    /// Held funds and total funds are decreased by amount. Fails if account is
-    /// locked or amount is negative.
-    pub fn chargeback(&mut self, amount: Decimal) {
-        match &mut self.inner {
-            AccountInner::Open { balance } => {
-                balance.chargeback(amount);
-                let balance = balance.clone();
-                self.inner = AccountInner::Frozen { balance };
+    /// Applies the inverse operation without any funds checks, as the
+    /// operation being undone was applied. Activity is not changed.
+    /// Chargebacks and reversals cannot be undone, returning
+    /// `OpResult::SkippedIrreversible`.
+    pub fn undo(&mut self, kind: &TransactionKind, tx: u32, amount: Decimal) -> OpResult {
+        let balance = match &mut self.inner {
+            AccountInner::Open { balance } | AccountInner::Flagged { balance } => balance,
+            AccountInner::Frozen { .. } => return OpResult::SkippedLocked,
+        };
+        match kind {
+            TransactionKind::Deposit
+            | TransactionKind::Adjustment
+            | TransactionKind::Correction => balance.adjust(-amount),
+            TransactionKind::Withdrawal | TransactionKind::Fee => balance.adjust(amount),
+            TransactionKind::Hold => {
+                let result = balance.resolve(amount);
+                if result == OpResult::Applied {
+                    release_held(&mut self.holds, tx, amount);
+                }
+                result
             }
-            _ => (),
+            TransactionKind::Dispute => {
+                let result = balance.resolve(amount);
+                if result == OpResult::Applied && self.release_held(tx, amount) {
+                    self.open_disputes = self.open_disputes.saturating_sub(1);
+                }
+                result
+            }
+            TransactionKind::Release => {
+                let result = balance.dispute(amount);
+                if result == OpResult::Applied {
+                    *self.holds.entry(tx).or_default() += amount;
+                }
+                result
+            }
+            TransactionKind::Resolve => {
+                let result = balance.dispute(amount);
+                if result == OpResult::Applied {
+                    if !self.held_by_tx.contains_key(&tx) {
+                        self.open_disputes += 1;
+                    }
+                    *self.held_by_tx.entry(tx).or_default() += amount;
+                }
+                result
+            }
+            TransactionKind::Chargeback | TransactionKind::Reversal => {
+                OpResult::SkippedIrreversible
+            }
+            TransactionKind::Unknown(_) => OpResult::SkippedUnknownKind,
         }
     }
-}
-
-// Client account representation.
-//
-// Accounts only have two states `Open` where transactions are permitted and
-// `Frozen` where all transactions are prohibited.
-#[derive(Debug, Eq, PartialEq)]
-enum AccountInner {
-    Open { balance: Balance },
-    Frozen { balance: Balance },
-}
 
-impl AccountInner {
-    fn new() -> Self {
-        Self::Open {
-            balance: Balance::new(),
+    /// Increase the available and total funds of the client account by amount.
+    ///
+    /// Only fails when the account is locked or amount is negative.
+    pub(crate) fn deposit(&mut self, amount: Decimal) -> OpResult {
+        let result = match &mut self.inner {
+            AccountInner::Open { balance } | AccountInner::Flagged { balance } => {
+                balance.deposit(amount)
+            }
+            AccountInner::Frozen { .. } => OpResult::SkippedLocked,
+        };
+        if result == OpResult::Applied {
+            self.activity.deposits += 1;
+            self.activity.deposited = self.activity.deposited.saturating_add(amount);
         }
+        result
     }
-}
 
-// Client account balance.
-//
-// Implements all balance manipulation operations.
-#[derive(Debug, Eq, PartialEq, Clone)]
-struct Balance {
-    available: Decimal,
-    held: Decimal,
-    total: Decimal,
-}
+    // Returns `true` if a deposit of `amount` would not overflow the funds.
+    pub(crate) fn deposit_fits(&self, amount: Decimal) -> bool {
+        self.balance().deposit_fits(amount)
+    }
 
-impl Balance {
-    fn new() -> Self {
-        Balance {
-            available: Decimal::new(0, SCALE),
-            held: Decimal::new(0, SCALE),
-            total: Decimal::new(0, SCALE),
+    // Register the open dispute of `tx` holding `amount`, already counted in
+    // the held funds, as resumed by `Engine::with_open_disputes`.
+    pub(crate) fn restore_dispute(&mut self, tx: u32, amount: Decimal) {
+        if self.held_by_tx.insert(tx, amount).is_none() {
+            self.open_disputes += 1;
         }
     }
 
-    fn deposit(&mut self, amount: Decimal) {
-        if amount > Decimal::ZERO {
-            self.available += amount;
-            self.total += amount;
+    /// Deposit the net `amount` of `count` coalesced deposits.
+    ///
+    /// Activity counts each of the deposits as when applied one by one.
+    pub(crate) fn deposit_coalesced(&mut self, amount: Decimal, count: usize) -> OpResult {
+        let result = self.deposit(amount);
+        if result == OpResult::Applied {
+            self.activity.deposits += count.saturating_sub(1);
         }
+        result
     }
 
-    fn withdraw(&mut self, amount: Decimal) {
-        if self.available > amount && amount > Decimal::ZERO {
-            self.available -= amount;
-            self.total -= amount;
+    /// Decrease the available and total funds of the client account by amount.
+    ///
+    /// Fails if account is locked, the account does not have sufficient
+    /// available funds, or if the amount is negative. When held funds would
+    /// have covered the withdrawal `OpResult::SkippedFundsHeld` is returned
+    /// rather than `OpResult::SkippedInsufficientFunds`.
+    pub fn withdraw(&mut self, amount: Decimal) -> OpResult {
+        let result = match &mut self.inner {
+            AccountInner::Open { balance } | AccountInner::Flagged { balance } => {
+                balance.withdraw(amount)
+            }
+            AccountInner::Frozen { .. } => OpResult::SkippedLocked,
+        };
+        if result == OpResult::Applied {
+            self.activity.withdrawals += 1;
+            self.activity.withdrawn = self.activity.withdrawn.saturating_add(amount);
         }
+        result
     }
 
-    fn dispute(&mut self, amount: Decimal) {
-        if amount > Decimal::ZERO {
-            self.available -= amount;
-            self.held += amount;
+    /// Manual correction of the available and total funds by a signed amount.
+    ///
+    /// Positive amounts credit and negative amounts debit the account without
+    /// the insufficient funds check of withdrawals. Fails if account is locked
+    /// or amount is zero.
+    pub fn adjust(&mut self, amount: Decimal) -> OpResult {
+        let result = match &mut self.inner {
+            AccountInner::Open { balance } | AccountInner::Flagged { balance } => {
+                balance.adjust(amount)
+            }
+            AccountInner::Frozen { .. } => OpResult::SkippedLocked,
+        };
+        if result == OpResult::Applied {
+            self.activity.adjustments += 1;
         }
+        result
     }
 
-    fn resolve(&mut self, amount: Decimal) {
-        if amount > Decimal::ZERO {
-            self.available += amount;
-            self.held -= amount;
+    /// Correction of a deposit or withdrawal by a signed amount.
+    ///
+    /// Applied as `adjust`, counted separately in `Activity`. Fails if account
+    /// is locked or amount is zero.
+    pub fn correct(&mut self, amount: Decimal) -> OpResult {
+        let result = match &mut self.inner {
+            AccountInner::Open { balance } | AccountInner::Flagged { balance } => {
+                balance.adjust(amount)
+            }
+            AccountInner::Frozen { .. } => OpResult::SkippedLocked,
+        };
+        if result == OpResult::Applied {
+            self.activity.corrections += 1;
         }
+        result
     }
 
-    fn chargeback(&mut self, amount: Decimal) {
-        if amount > Decimal::ZERO {
-            self.held -= amount;
-            self.total -= amount;
+    /// Reversal of a deposit or withdrawal by a signed amount.
+    ///
+    /// Positive amounts credit back a reversed withdrawal and negative amounts
+    /// debit a reversed deposit, failing if the account does not have
+    /// sufficient available funds. Fails if account is locked or amount is
+    /// zero.
+    pub fn reverse(&mut self, amount: Decimal) -> OpResult {
+        let result = match &mut self.inner {
+            AccountInner::Open { balance } | AccountInner::Flagged { balance } => {
+                if amount < Decimal::ZERO && balance.available() + amount < Decimal::ZERO {
+                    return OpResult::SkippedInsufficientFunds;
+                }
+                balance.adjust(amount)
+            }
+            AccountInner::Frozen { .. } => OpResult::SkippedLocked,
+        };
+        if result == OpResult::Applied {
+            self.activity.reversals += 1;
         }
+        result
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn client_new() {
-        let zero = Decimal::ZERO;
-        let client = Client::new(42);
+    /// Service fee decreasing the available and total funds by amount.
+    ///
+    /// Fails if account is locked, the account does not have sufficient
+    /// available funds, or amount is not positive.
+    pub fn fee(&mut self, amount: Decimal) -> OpResult {
+        self.fee_with_policy(amount, FeePolicy::Refuse)
+    }
 
-        assert_eq!(
-            client,
-            Client {
-                id: 42,
-                account: Account {
-                    inner: AccountInner::Open {
-                        balance: Balance {
-                            available: zero,
-                            held: zero,
-                            total: zero
-                        }
-                    }
-                },
+    /// Service fee as `fee`, under `FeePolicy::Overdraft` the fee is charged
+    /// even when it exceeds the available funds.
+    pub fn fee_with_policy(&mut self, amount: Decimal, policy: FeePolicy) -> OpResult {
+        let result = match &mut self.inner {
+            AccountInner::Open { balance } | AccountInner::Flagged { balance } => {
+                if amount <= Decimal::ZERO {
+                    return OpResult::SkippedInvalidAmount;
+                }
+                if policy == FeePolicy::Refuse && balance.available() < amount {
+                    return OpResult::SkippedInsufficientFunds;
+                }
+                balance.adjust(-amount)
             }
-        );
+            AccountInner::Frozen { .. } => OpResult::SkippedLocked,
+        };
+        if result == OpResult::Applied {
+            self.activity.fees += 1;
+        }
+        result
     }
 
-    #[test]
-    fn client_new_constraints() {
-        let client = Client::new(u16::MIN);
-        assert_eq!(
-            client,
-            Client {
+    /// Administrative hold `tx` moving funds to held.
+    ///
+    /// Available funds decreased by amount, held funds increased by amount,
+    /// total funds remain the same. Fails if account is locked, `tx` has an
+    /// open dispute, the account does not have sufficient available funds, or
+    /// amount is negative.
+    pub fn hold(&mut self, tx: u32, amount: Decimal) -> OpResult {
+        if self.held_by_tx.contains_key(&tx) {
+            return OpResult::SkippedDisputed;
+        }
+        let result = match &mut self.inner {
+            AccountInner::Open { balance } | AccountInner::Flagged { balance } => {
+                if balance.available() < amount {
+                    return OpResult::SkippedInsufficientFunds;
+                }
+                balance.dispute(amount)
+            }
+            AccountInner::Frozen { .. } => OpResult::SkippedLocked,
+        };
+        if result == OpResult::Applied {
+            self.activity.holds += 1;
+            *self.holds.entry(tx).or_default() += amount;
+        }
+        result
+    }
+
+    /// Release of administrative hold `tx`, moving funds back to available.
+    ///
+    /// Held funds decreased by amount, available funds increased by amount,
+    /// total funds remain the same. Fails if account is locked, `tx` has an
+    /// open dispute, hold `tx` holds less than amount, or amount is negative.
+    pub fn release(&mut self, tx: u32, amount: Decimal) -> OpResult {
+        if self.held_by_tx.contains_key(&tx) {
+            return OpResult::SkippedDisputed;
+        }
+        let held = self.holds.get(&tx).copied().unwrap_or_default();
+        let result = match &mut self.inner {
+            AccountInner::Open { balance } | AccountInner::Flagged { balance } => {
+                if held < amount {
+                    return OpResult::SkippedInsufficientFunds;
+                }
+                balance.resolve(amount)
+            }
+            AccountInner::Frozen { .. } => OpResult::SkippedLocked,
+        };
+        if result == OpResult::Applied {
+            self.activity.releases += 1;
+            release_held(&mut self.holds, tx, amount);
+        }
+        result
+    }
+
+    /// Associated funds of transaction `tx` moved to held.
+    ///
+    /// Available funds decreased by amount, held funds increased by amount,
+    /// total funds remain the same. Fails if account is locked or amount is
+    /// negative.
+    pub fn dispute(&mut self, tx: u32, amount: Decimal) -> OpResult {
+        self.dispute_with_policy(tx, amount, DisputePolicy::Overdraft)
+    }
+
+    /// Dispute as `dispute`, an amount exceeding the available funds is
+    /// handled as `policy` directs.
+    pub fn dispute_with_policy(
+        &mut self,
+        tx: u32,
+        amount: Decimal,
+        policy: DisputePolicy,
+    ) -> OpResult {
+        let result = match &mut self.inner {
+            AccountInner::Open { balance } | AccountInner::Flagged { balance } => {
+                if policy == DisputePolicy::Refuse && balance.available() < amount {
+                    return OpResult::SkippedInsufficientFunds;
+                }
+                balance.dispute(amount)
+            }
+            AccountInner::Frozen { .. } => OpResult::SkippedLocked,
+        };
+        if result == OpResult::Applied {
+            self.activity.disputes += 1;
+            if !self.held_by_tx.contains_key(&tx) {
+                self.open_disputes += 1;
+            }
+            *self.held_by_tx.entry(tx).or_default() += amount;
+        }
+        result
+    }
+
+    /// Resolution to a dispute of transaction `tx`, releases held funds.
+    ///
+    /// Held funds decreased by amount, available funds increased by amount,
+    /// total funds remain the same. Fails if account is locked or amount is
+    /// negative.
+    pub fn resolve(&mut self, tx: u32, amount: Decimal) -> OpResult {
+        let result = match &mut self.inner {
+            AccountInner::Open { balance } | AccountInner::Flagged { balance } => {
+                balance.resolve(amount)
+            }
+            AccountInner::Frozen { .. } => OpResult::SkippedLocked,
+        };
+        if result == OpResult::Applied {
+            self.activity.resolves += 1;
+            if self.release_held(tx, amount) {
+                self.open_disputes = self.open_disputes.saturating_sub(1);
+            }
+        }
+        result
+    }
+
+    /// Final state of a dispute of transaction `tx` and represents the client
+    /// reversing a transaction.
+    ///
+    /// Held funds and total funds are decreased by amount. Fails if account is
+    /// locked or amount is negative.
+    pub fn chargeback(&mut self, tx: u32, amount: Decimal) -> OpResult {
+        self.chargeback_with_policy(
+            tx,
+            amount,
+            ChargebackPolicy::Freeze,
+            ExcessChargebackPolicy::Allow,
+        )
+    }
+
+    /// Chargeback as `chargeback`, leaving the account as `policy` directs.
+    ///
+    /// An amount exceeding the funds held by the dispute of `tx`, or all held
+    /// funds if the dispute is untracked, is clamped to those funds so held
+    /// never goes negative. The shortfall is recorded in `Activity` and
+    /// `OpResult::Clamped` returned. An amount exceeding the total funds is
+    /// handled as `excess` directs, returning `OpResult::Clamped` or
+    /// `OpResult::SkippedExceedsTotal`.
+    pub fn chargeback_with_policy(
+        &mut self,
+        tx: u32,
+        mut amount: Decimal,
+        policy: ChargebackPolicy,
+        excess: ExcessChargebackPolicy,
+    ) -> OpResult {
+        let mut clamped = false;
+        let mut shortfall = Decimal::ZERO;
+        let result = match &mut self.inner {
+            AccountInner::Open { balance } | AccountInner::Flagged { balance } => {
+                let held = self.held_by_tx.get(&tx).copied().unwrap_or(balance.held());
+                // Nothing is held to charge back, the account is left as is.
+                if held <= Decimal::ZERO {
+                    return OpResult::SkippedInvalidAmount;
+                }
+                if amount > held {
+                    shortfall = amount - held;
+                    amount -= shortfall;
+                    clamped = true;
+                }
+                if amount > balance.total() {
+                    match excess {
+                        ExcessChargebackPolicy::Allow => {}
+                        ExcessChargebackPolicy::Clamp => {
+                            amount = balance.total().max(Decimal::ZERO);
+                            clamped = true;
+                        }
+                        ExcessChargebackPolicy::Refuse => return OpResult::SkippedExceedsTotal,
+                    }
+                }
+                let result = balance.chargeback(amount);
+                // Refused chargebacks leave the account as is.
+                if result == OpResult::Applied {
+                    let balance = balance.clone();
+                    self.inner = match policy {
+                        ChargebackPolicy::Freeze => AccountInner::Frozen { balance },
+                        ChargebackPolicy::Flag => AccountInner::Flagged { balance },
+                    };
+                }
+                result
+            }
+            AccountInner::Frozen { .. } => OpResult::SkippedLocked,
+        };
+        if result == OpResult::Applied {
+            self.activity.chargebacks += 1;
+            self.activity.chargeback_shortfall =
+                self.activity.chargeback_shortfall.saturating_add(shortfall);
+            if policy == ChargebackPolicy::Freeze {
+                self.frozen_at_tx = Some(tx);
+            }
+            // The dispute is closed, clamped chargebacks included, so nothing
+            // remains held by it.
+            if self.held_by_tx.remove(&tx).is_some() {
+                self.open_disputes = self.open_disputes.saturating_sub(1);
+            }
+            if clamped {
+                return OpResult::Clamped;
+            }
+        }
+        result
+    }
+
+    // Clamp negative available, held and total funds to zero.
+    pub(crate) fn saturate(&mut self) {
+        match &mut self.inner {
+            AccountInner::Open { balance }
+            | AccountInner::Flagged { balance }
+            | AccountInner::Frozen { balance } => balance.saturate(),
+        }
+    }
+
+    // Remove amount from the funds held by the dispute of transaction `tx`,
+    // returning `true` if none remain held.
+    fn release_held(&mut self, tx: u32, amount: Decimal) -> bool {
+        release_held(&mut self.held_by_tx, tx, amount)
+    }
+
+    /// Move available funds from this account to `other`.
+    ///
+    /// Available and total funds are decreased by amount here and increased
+    /// by amount in `other`. Fails if either account is locked, this account
+    /// does not have sufficient available funds, or amount is negative.
+    pub fn transfer_to(&mut self, other: &mut Account<M>, amount: Decimal) -> OpResult {
+        match (&mut self.inner, &mut other.inner) {
+            (
+                AccountInner::Open { balance } | AccountInner::Flagged { balance },
+                AccountInner::Open { balance: other } | AccountInner::Flagged { balance: other },
+            ) => {
+                if amount <= Decimal::ZERO {
+                    return OpResult::SkippedInvalidAmount;
+                }
+                match balance.transfer_to(amount) {
+                    Some(amount) => other.deposit(amount),
+                    None => OpResult::SkippedInsufficientFunds,
+                }
+            }
+            _ => OpResult::SkippedLocked,
+        }
+    }
+}
+
+// Client account representation.
+//
+// Accounts have two primary states `Open` where transactions are permitted
+// and `Frozen` where all transactions are prohibited. `Flagged` accounts have
+// had a chargeback but permit transactions as when `Open`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum AccountInner<M: Money = Decimal> {
+    Open { balance: Balance<M> },
+    Flagged { balance: Balance<M> },
+    Frozen { balance: Balance<M> },
+}
+
+impl<M: Money> AccountInner<M> {
+    fn new() -> Self {
+        Self::Open {
+            balance: Balance::new(),
+        }
+    }
+}
+
+impl Add for Balance {
+    type Output = Balance;
+
+    fn add(mut self, other: Balance) -> Balance {
+        self += other;
+        self
+    }
+}
+
+impl AddAssign for Balance {
+    fn add_assign(&mut self, other: Balance) {
+        self.available += other.available;
+        self.held += other.held;
+        self.total += other.total;
+    }
+}
+
+impl Sum for Balance {
+    fn sum<I: Iterator<Item = Balance>>(iter: I) -> Balance {
+        iter.fold(Balance::new(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Balance> for Balance {
+    fn sum<I: Iterator<Item = &'a Balance>>(iter: I) -> Balance {
+        iter.cloned().sum()
+    }
+}
+
+// Remove amount from the funds of transaction `tx` in `held`, returning `true`
+// if none remain held.
+fn release_held(held: &mut HashMap<u32, Decimal>, tx: u32, amount: Decimal) -> bool {
+    if let Some(funds) = held.get_mut(&tx) {
+        *funds -= amount;
+        if *funds <= Decimal::ZERO {
+            held.remove(&tx);
+            return true;
+        }
+    }
+    false
+}
+
+/// Client account balance.
+///
+/// Implements all balance manipulation operations, read only outside of an
+/// `Account`. `Decimal` balances add field-wise, summing them gives grand
+/// totals across clients. Funds are kept in `M` and read as `Decimal`,
+/// amounts that `M` cannot represent are refused as invalid and operations
+/// overflowing `M` are refused with `OpResult::SkippedOverflow`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Balance<M: Money = Decimal> {
+    available: M,
+    held: M,
+    total: M,
+}
+
+impl<M: Money> Balance<M> {
+    fn new() -> Self {
+        Balance::with_scale(DEFAULT_SCALE)
+    }
+
+    pub(crate) fn with_scale(scale: u32) -> Self {
+        Balance {
+            available: M::zero(scale),
+            held: M::zero(scale),
+            total: M::zero(scale),
+        }
+    }
+
+    /// Returns the funds available for withdrawal.
+    pub fn available(&self) -> Decimal {
+        self.available.to_decimal()
+    }
+
+    /// Returns the funds held by open disputes.
+    pub fn held(&self) -> Decimal {
+        self.held.to_decimal()
+    }
+
+    /// Returns the total funds, available and held.
+    pub fn total(&self) -> Decimal {
+        self.total.to_decimal()
+    }
+
+    /// Returns `true` if available, held and total funds are all zero.
+    pub fn is_zero(&self) -> bool {
+        let zero = M::default();
+        self.available == zero && self.held == zero && self.total == zero
+    }
+
+    // Returns `amount` in `M` if it is positive and representable.
+    fn positive(amount: Decimal) -> Option<M> {
+        M::from_decimal(amount).filter(|amount| *amount > M::default())
+    }
+
+    fn deposit(&mut self, amount: Decimal) -> OpResult {
+        let Some(amount) = Self::positive(amount) else {
+            return OpResult::SkippedInvalidAmount;
+        };
+
+        self.update(|available, held, total| {
+            Some((
+                available.checked_add(amount)?,
+                held,
+                total.checked_add(amount)?,
+            ))
+        })
+    }
+
+    fn withdraw(&mut self, amount: Decimal) -> OpResult {
+        let Some(amount) = Self::positive(amount) else {
+            return OpResult::SkippedInvalidAmount;
+        };
+        if self.available <= amount {
+            if self.total > amount {
+                return OpResult::SkippedFundsHeld;
+            }
+            return OpResult::SkippedInsufficientFunds;
+        }
+
+        self.update(|available, held, total| {
+            Some((
+                available.checked_sub(amount)?,
+                held,
+                total.checked_sub(amount)?,
+            ))
+        })
+    }
+
+    fn adjust(&mut self, amount: Decimal) -> OpResult {
+        let Some(amount) = M::from_decimal(amount).filter(|amount| *amount != M::default()) else {
+            return OpResult::SkippedInvalidAmount;
+        };
+
+        self.update(|available, held, total| {
+            Some((
+                available.checked_add(amount)?,
+                held,
+                total.checked_add(amount)?,
+            ))
+        })
+    }
+
+    fn dispute(&mut self, amount: Decimal) -> OpResult {
+        let Some(amount) = Self::positive(amount) else {
+            return OpResult::SkippedInvalidAmount;
+        };
+
+        self.update(|available, held, total| {
+            Some((
+                available.checked_sub(amount)?,
+                held.checked_add(amount)?,
+                total,
+            ))
+        })
+    }
+
+    fn resolve(&mut self, amount: Decimal) -> OpResult {
+        let Some(amount) = Self::positive(amount) else {
+            return OpResult::SkippedInvalidAmount;
+        };
+
+        self.update(|available, held, total| {
+            Some((
+                available.checked_add(amount)?,
+                held.checked_sub(amount)?,
+                total,
+            ))
+        })
+    }
+
+    fn chargeback(&mut self, amount: Decimal) -> OpResult {
+        let Some(amount) = Self::positive(amount) else {
+            return OpResult::SkippedInvalidAmount;
+        };
+
+        self.update(|available, held, total| {
+            Some((
+                available,
+                held.checked_sub(amount)?,
+                total.checked_sub(amount)?,
+            ))
+        })
+    }
+
+    // Replace the available, held and total funds with those returned by
+    // `update`, refusing the operation if it returns `None` on overflow.
+    fn update(&mut self, update: impl FnOnce(M, M, M) -> Option<(M, M, M)>) -> OpResult {
+        match update(self.available, self.held, self.total) {
+            Some((available, held, total)) => {
+                self.available = available;
+                self.held = held;
+                self.total = total;
+                OpResult::Applied
+            }
+            None => OpResult::SkippedOverflow,
+        }
+    }
+
+    // Returns `true` if `amount` can be added to the available and total
+    // funds without overflow.
+    fn deposit_fits(&self, amount: Decimal) -> bool {
+        M::from_decimal(amount).is_some_and(|amount| {
+            self.available.checked_add(amount).is_some() && self.total.checked_add(amount).is_some()
+        })
+    }
+
+    // Returns the field-wise sum of both balances, or `None` on overflow.
+    fn checked_add(&self, other: &Balance<M>) -> Option<Balance<M>> {
+        Some(Balance {
+            available: self.available.checked_add(other.available)?,
+            held: self.held.checked_add(other.held)?,
+            total: self.total.checked_add(other.total)?,
+        })
+    }
+
+    fn saturate(&mut self) {
+        self.available = self.available.max(M::default());
+        self.held = self.held.max(M::default());
+        self.total = self.total.max(M::default());
+    }
+
+    fn available_scale(&self) -> u32 {
+        self.available().scale()
+    }
+
+    fn held_scale(&self) -> u32 {
+        self.held().scale()
+    }
+
+    fn total_scale(&self) -> u32 {
+        self.total().scale()
+    }
+
+    // Returns the available, held and total funds as integer counts of minor
+    // units at `scale`, rounding half to even, or `None` on overflow.
+    fn to_minor_units(&self, scale: u32) -> Option<(i64, i64, i64)> {
+        let unit = (0..scale).try_fold(Decimal::ONE, |unit, _| unit.checked_mul(Decimal::TEN))?;
+        let minor = |value: Decimal| value.checked_mul(unit)?.round().to_i64();
+
+        Some((
+            minor(self.available())?,
+            minor(self.held())?,
+            minor(self.total())?,
+        ))
+    }
+
+    // Debit amount from the available and total funds, returning the amount
+    // to credit to the receiving balance or `None` if funds are insufficient.
+    fn transfer_to(&mut self, amount: Decimal) -> Option<Decimal> {
+        let debit = Self::positive(amount)?;
+        if self.available < debit {
+            return None;
+        }
+
+        let result = self.update(|available, held, total| {
+            Some((
+                available.checked_sub(debit)?,
+                held,
+                total.checked_sub(debit)?,
+            ))
+        });
+        (result == OpResult::Applied).then_some(amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_new() {
+        let zero = Decimal::ZERO;
+        let client = Client::new(42);
+
+        assert_eq!(
+            client,
+            Client {
+                id: 42,
+                account: Account {
+                    inner: AccountInner::Open {
+                        balance: Balance {
+                            available: zero,
+                            held: zero,
+                            total: zero
+                        }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn client_new_constraints() {
+        let client = Client::new(u16::MIN);
+        assert_eq!(
+            client,
+            Client {
                 id: u16::MIN,
                 account: Account::new(),
             }
@@ -279,6 +1615,109 @@ mod tests {
         );
     }
 
+    #[test]
+    fn client_balances_equal() {
+        let mut client = Client::new(1);
+        client.account.inner = AccountInner::Open {
+            balance: Balance {
+                available: Decimal::new(0, 2),
+                held: Decimal::new(0, 0),
+                total: Decimal::new(0, 28),
+            },
+        };
+        assert!(client.balances_equal(&Client::new(2)));
+
+        client.get_mut().deposit(Decimal::ONE);
+        assert!(!client.balances_equal(&Client::new(1)));
+    }
+
+    #[test]
+    fn client_merge_into() {
+        let one = Decimal::ONE;
+        let ten = Decimal::TEN;
+        let mut client = Client::new(1);
+        client.get_mut().deposit(ten);
+        client.get_mut().dispute(1, one);
+        let mut other = Client::new(2);
+        other.get_mut().deposit(ten);
+        other.get_mut().dispute(2, ten);
+
+        assert_eq!(client.merge_into(other), Ok(()));
+        assert_eq!(
+            client.balance(),
+            &Balance {
+                available: ten - one,
+                held: ten + one,
+                total: ten + ten,
+            }
+        );
+        assert_eq!(
+            client.get().held_breakdown(),
+            &HashMap::from([(1, one), (2, ten)])
+        );
+        assert_eq!(client.open_dispute_count(), 2);
+        assert!(!client.is_locked());
+
+        // Merging a locked account locks the merged account.
+        let mut locked = Client::new(3);
+        locked.get_mut().deposit(ten);
+        locked.get_mut().dispute(3, one);
+        locked.get_mut().chargeback(3, one);
+        assert_eq!(client.merge_into(locked), Ok(()));
+        assert_eq!(
+            client.balance(),
+            &Balance {
+                available: ten + ten - one - one,
+                held: ten + one,
+                total: ten + ten + ten - one,
+            }
+        );
+        assert_eq!(
+            client.get().held_breakdown(),
+            &HashMap::from([(1, one), (2, ten)])
+        );
+        assert!(client.is_locked());
+    }
+
+    #[test]
+    fn client_merge_into_conflict() {
+        let one = Decimal::ONE;
+        let mut client = Client::new(1);
+        client.get_mut().deposit(Decimal::TEN);
+        client.get_mut().dispute(1, one);
+        let mut other = Client::new(2);
+        other.get_mut().deposit(Decimal::TEN);
+        other.get_mut().dispute(1, Decimal::TEN);
+        let before = client.clone();
+
+        // Both clients hold funds under Transaction ID 1.
+        assert_eq!(
+            client.merge_into(other),
+            Err(KoncordError::MergeConflict { tx: 1 })
+        );
+        assert_eq!(client, before);
+        assert_eq!(client.get().held_breakdown(), &HashMap::from([(1, one)]));
+
+        let mut large = Client::new(3);
+        large.get_mut().deposit(Decimal::MAX);
+        assert_eq!(client.merge_into(large), Err(KoncordError::MergeOverflow));
+        assert_eq!(client, before);
+    }
+
+    #[test]
+    fn client_cmp_report() {
+        let mut clients: Vec<Client> = (1..=4).map(Client::new).collect();
+        clients[0].get_mut().deposit(Decimal::ONE);
+        clients[1].get_mut().deposit(Decimal::TEN);
+        clients[2].get_mut().deposit(Decimal::ONE);
+        clients.sort_by(Client::cmp_report);
+
+        let ids: Vec<u16> = clients.iter().map(Client::id).collect();
+        assert_eq!(ids, vec![2, 1, 3, 4]);
+        assert_eq!(clients[1].cmp_report(&clients[2]), Ordering::Less);
+        assert_eq!(clients[1].cmp(&clients[2]), Ordering::Less);
+    }
+
     #[test]
     fn client_deposit() {
         let zero = Decimal::ZERO;
@@ -297,7 +1736,12 @@ mod tests {
                             held: zero,
                             total: one_billion_dollars
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
@@ -311,8 +1755,8 @@ mod tests {
         let mut client = Client::new(1337);
         client.get_mut().deposit(one);
         client.get_mut().deposit(one_billion_dollars);
-        client.get_mut().dispute(one_billion_dollars);
-        client.get_mut().chargeback(one_billion_dollars);
+        client.get_mut().dispute(1, one_billion_dollars);
+        client.get_mut().chargeback(1, one_billion_dollars);
         client.get_mut().deposit(one_billion_dollars);
 
         assert_eq!(
@@ -326,7 +1770,12 @@ mod tests {
                             held: zero,
                             total: one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
@@ -347,7 +1796,12 @@ mod tests {
                             held: zero,
                             total: one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
@@ -375,7 +1829,12 @@ mod tests {
                             held: zero,
                             total: one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
@@ -385,8 +1844,8 @@ mod tests {
         let mut client = Client::new(1337);
         client.get_mut().deposit(leet);
         client.get_mut().deposit(one_billion_dollars);
-        client.get_mut().dispute(one_billion_dollars);
-        client.get_mut().chargeback(one_billion_dollars);
+        client.get_mut().dispute(1, one_billion_dollars);
+        client.get_mut().chargeback(1, one_billion_dollars);
         client.get_mut().withdraw(one);
 
         assert_eq!(
@@ -400,7 +1859,12 @@ mod tests {
                             held: zero,
                             total: leet,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
@@ -420,7 +1884,12 @@ mod tests {
                             held: zero,
                             total: one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
@@ -439,14 +1908,19 @@ mod tests {
                             held: zero,
                             total: zero,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
 
         // Withdrawal should fail on insufficient funds no matter how small.
         let mut client = Client::new(101);
-        client.get_mut().withdraw(Decimal::new(1, SCALE));
+        client.get_mut().withdraw(Decimal::new(1, DEFAULT_SCALE));
         assert_eq!(
             client,
             Client {
@@ -458,7 +1932,12 @@ mod tests {
                             held: zero,
                             total: zero,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
@@ -475,7 +1954,12 @@ mod tests {
                             held: zero,
                             total: zero,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
@@ -490,7 +1974,7 @@ mod tests {
         let mut client = Client::new(42);
         client.get_mut().deposit(one);
         client.get_mut().deposit(one_billion_dollars);
-        client.get_mut().dispute(one_billion_dollars);
+        client.get_mut().dispute(1, one_billion_dollars);
 
         assert_eq!(
             client,
@@ -503,14 +1987,19 @@ mod tests {
                             held: one_billion_dollars,
                             total: one_billion_dollars + one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
 
         // Dispute should fail on locked account.
-        client.get_mut().chargeback(one_billion_dollars);
-        client.get_mut().dispute(one_billion_dollars);
+        client.get_mut().chargeback(1, one_billion_dollars);
+        client.get_mut().dispute(1, one_billion_dollars);
         assert_eq!(
             client,
             Client {
@@ -522,7 +2011,12 @@ mod tests {
                             held: zero,
                             total: one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
@@ -531,7 +2025,7 @@ mod tests {
         let mut client = Client::new(24);
         client.get_mut().deposit(one);
         client.get_mut().deposit(one_billion_dollars);
-        client.get_mut().dispute(negative_one);
+        client.get_mut().dispute(1, negative_one);
         assert_eq!(
             client,
             Client {
@@ -543,7 +2037,12 @@ mod tests {
                             held: zero,
                             total: one_billion_dollars + one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
@@ -558,8 +2057,8 @@ mod tests {
         let mut client = Client::new(42);
         client.get_mut().deposit(one);
         client.get_mut().deposit(one_billion_dollars);
-        client.get_mut().dispute(one_billion_dollars);
-        client.get_mut().resolve(one_billion_dollars);
+        client.get_mut().dispute(1, one_billion_dollars);
+        client.get_mut().resolve(1, one_billion_dollars);
 
         assert_eq!(
             client,
@@ -572,15 +2071,20 @@ mod tests {
                             held: zero,
                             total: one_billion_dollars + one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
 
         // Dispute should fail on locked account.
-        client.get_mut().dispute(one_billion_dollars);
-        client.get_mut().chargeback(one_billion_dollars);
-        client.get_mut().resolve(one_billion_dollars);
+        client.get_mut().dispute(1, one_billion_dollars);
+        client.get_mut().chargeback(1, one_billion_dollars);
+        client.get_mut().resolve(1, one_billion_dollars);
         assert_eq!(
             client,
             Client {
@@ -592,7 +2096,12 @@ mod tests {
                             held: zero,
                             total: one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
@@ -601,8 +2110,8 @@ mod tests {
         let mut client = Client::new(24);
         client.get_mut().deposit(one);
         client.get_mut().deposit(one_billion_dollars);
-        client.get_mut().dispute(one);
-        client.get_mut().resolve(negative_one);
+        client.get_mut().dispute(1, one);
+        client.get_mut().resolve(1, negative_one);
         assert_eq!(
             client,
             Client {
@@ -614,7 +2123,12 @@ mod tests {
                             held: one,
                             total: one_billion_dollars + one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
@@ -629,8 +2143,8 @@ mod tests {
         let mut client = Client::new(42);
         client.get_mut().deposit(one);
         client.get_mut().deposit(one_billion_dollars);
-        client.get_mut().dispute(one_billion_dollars);
-        client.get_mut().chargeback(one_billion_dollars);
+        client.get_mut().dispute(1, one_billion_dollars);
+        client.get_mut().chargeback(1, one_billion_dollars);
 
         assert_eq!(
             client,
@@ -643,7 +2157,12 @@ mod tests {
                             held: zero,
                             total: one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
@@ -663,7 +2182,12 @@ mod tests {
                             held: zero,
                             total: one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
@@ -683,15 +2207,20 @@ mod tests {
                             held: zero,
                             total: one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
 
         // Disputes should fail after chargeback.
-        client.get_mut().dispute(one_billion_dollars);
-        client.get_mut().dispute(one);
-        client.get_mut().dispute(negative_one);
+        client.get_mut().dispute(1, one_billion_dollars);
+        client.get_mut().dispute(1, one);
+        client.get_mut().dispute(1, negative_one);
         assert_eq!(
             client,
             Client {
@@ -703,15 +2232,20 @@ mod tests {
                             held: zero,
                             total: one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
 
         // Resolutions should fail after chargeback.
-        client.get_mut().resolve(one_billion_dollars);
-        client.get_mut().resolve(one);
-        client.get_mut().resolve(negative_one);
+        client.get_mut().resolve(1, one_billion_dollars);
+        client.get_mut().resolve(1, one);
+        client.get_mut().resolve(1, negative_one);
         assert_eq!(
             client,
             Client {
@@ -723,15 +2257,20 @@ mod tests {
                             held: zero,
                             total: one,
                         }
-                    }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
                 },
             }
         );
 
         // Chargebacks should fail after chargeback.
-        client.get_mut().chargeback(one_billion_dollars);
-        client.get_mut().chargeback(one);
-        client.get_mut().chargeback(negative_one);
+        client.get_mut().chargeback(1, one_billion_dollars);
+        client.get_mut().chargeback(1, one);
+        client.get_mut().chargeback(1, negative_one);
         assert_eq!(
             client,
             Client {
@@ -743,10 +2282,732 @@ mod tests {
                             held: zero,
                             total: one,
                         }
+                    },
+                    held_by_tx: HashMap::new(),
+                    holds: HashMap::new(),
+                    open_disputes: 0,
+                    activity: Activity::default(),
+                    frozen_at_tx: None,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn account_apply() {
+        let zero = Decimal::ZERO;
+        let one = Decimal::ONE;
+        let ten = Decimal::TEN;
+        let mut account = Account::new();
+
+        assert_eq!(
+            account.apply(&TransactionKind::Deposit, 1, ten),
+            OpResult::Applied
+        );
+        assert_eq!(
+            account.apply(&TransactionKind::Withdrawal, 1, one),
+            OpResult::Applied
+        );
+        assert_eq!(
+            account.apply(&TransactionKind::Dispute, 1, one),
+            OpResult::Applied
+        );
+        assert_eq!(
+            account,
+            Account {
+                inner: AccountInner::Open {
+                    balance: Balance {
+                        available: ten - one - one,
+                        held: one,
+                        total: ten - one,
+                    }
+                },
+                held_by_tx: HashMap::new(),
+                holds: HashMap::new(),
+                open_disputes: 0,
+                activity: Activity::default(),
+                frozen_at_tx: None,
+            }
+        );
+
+        assert_eq!(
+            account.apply(&TransactionKind::Resolve, 1, one),
+            OpResult::Applied
+        );
+        assert_eq!(
+            account.apply(&TransactionKind::Dispute, 1, one),
+            OpResult::Applied
+        );
+        assert_eq!(
+            account.apply(&TransactionKind::Chargeback, 1, one),
+            OpResult::Applied
+        );
+        assert_eq!(
+            account,
+            Account {
+                inner: AccountInner::Frozen {
+                    balance: Balance {
+                        available: ten - one - one,
+                        held: zero,
+                        total: ten - one - one,
                     }
                 },
+                held_by_tx: HashMap::new(),
+                holds: HashMap::new(),
+                open_disputes: 0,
+                activity: Activity::default(),
+                frozen_at_tx: None,
             }
         );
+
+        // Operations are refused once the account is locked.
+        assert_eq!(
+            account.apply(&TransactionKind::Deposit, 1, ten),
+            OpResult::SkippedLocked
+        );
+    }
+
+    #[test]
+    fn account_apply_skipped() {
+        let one = Decimal::ONE;
+        let mut account: Account = Account::new();
+
+        assert_eq!(
+            account.apply(&TransactionKind::Deposit, 1, Decimal::NEGATIVE_ONE),
+            OpResult::SkippedInvalidAmount
+        );
+        assert_eq!(
+            account.apply(&TransactionKind::Withdrawal, 1, one),
+            OpResult::SkippedInsufficientFunds
+        );
+        assert_eq!(account, Account::new());
+    }
+
+    #[test]
+    fn account_held_breakdown() {
+        let one = Decimal::ONE;
+        let ten = Decimal::TEN;
+        let mut account: Account = Account::new();
+        account.deposit(ten);
+        account.deposit(one);
+        account.deposit(one);
+
+        assert_eq!(account.dispute(1, ten), OpResult::Applied);
+        assert_eq!(account.dispute(2, one), OpResult::Applied);
+        assert_eq!(
+            account.held_breakdown(),
+            &HashMap::from([(1, ten), (2, one)])
+        );
+        assert_eq!(
+            account.held_breakdown().values().sum::<Decimal>(),
+            account.held()
+        );
+
+        // Resolved and charged back disputes leave the breakdown.
+        assert_eq!(account.resolve(1, ten), OpResult::Applied);
+        assert_eq!(account.held_breakdown(), &HashMap::from([(2, one)]));
+        assert_eq!(account.chargeback(2, one), OpResult::Applied);
+        assert!(account.held_breakdown().is_empty());
+    }
+
+    #[test]
+    fn account_chargeback_flagged() {
+        let one = Decimal::ONE;
+        let ten = Decimal::TEN;
+        let mut account = Account::new();
+        account.deposit(ten);
+        account.dispute(1, one);
+
+        assert_eq!(
+            account.chargeback_with_policy(
+                1,
+                one,
+                ChargebackPolicy::Flag,
+                ExcessChargebackPolicy::Allow
+            ),
+            OpResult::Applied
+        );
+        assert!(account.is_flagged());
+        assert!(!account.is_locked());
+
+        // Flagged accounts remain usable.
+        assert_eq!(account.deposit(one), OpResult::Applied);
+        assert_eq!(account.withdraw(one), OpResult::Applied);
+        assert_eq!(
+            account,
+            Account {
+                inner: AccountInner::Flagged {
+                    balance: Balance {
+                        available: ten - one,
+                        held: Decimal::ZERO,
+                        total: ten - one,
+                    }
+                },
+                held_by_tx: HashMap::new(),
+                holds: HashMap::new(),
+                open_disputes: 0,
+                activity: Activity::default(),
+                frozen_at_tx: None,
+            }
+        );
+
+        // A chargeback under the default policy still locks the account.
+        account.dispute(2, one);
+        assert_eq!(account.chargeback(2, one), OpResult::Applied);
+        assert!(account.is_locked());
+        assert!(!account.is_flagged());
+    }
+
+    #[test]
+    fn account_withdraw_funds_held() {
+        let one = Decimal::ONE;
+        let ten = Decimal::TEN;
+        let mut account: Account = Account::new();
+        account.deposit(ten);
+        account.dispute(1, ten);
+
+        // All funds are held by the dispute.
+        assert_eq!(account.withdraw(one), OpResult::SkippedFundsHeld);
+        assert_eq!(account.withdraw(ten), OpResult::SkippedInsufficientFunds);
+
+        assert_eq!(account.resolve(1, ten), OpResult::Applied);
+        assert_eq!(account.withdraw(one), OpResult::Applied);
+        assert_eq!(account.available(), ten - one);
+        assert_eq!(account.held(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn account_chargeback_excess() {
+        let hundred = Decimal::new(100, 0);
+        let eighty = Decimal::new(80, 0);
+        let twenty = Decimal::new(20, 0);
+
+        // The deposit is disputed after most of it was withdrawn.
+        let disputed = || {
+            let mut account = Account::new();
+            account.deposit(hundred);
+            account.withdraw(eighty);
+            account.dispute(1, hundred);
+            assert_eq!(account.held(), hundred);
+            assert_eq!(account.total(), twenty);
+            account
+        };
+        let chargeback = |account: &mut Account, excess| {
+            account.chargeback_with_policy(1, hundred, ChargebackPolicy::Freeze, excess)
+        };
+
+        let mut account = disputed();
+        assert_eq!(
+            chargeback(&mut account, ExcessChargebackPolicy::Allow),
+            OpResult::Applied
+        );
+        assert_eq!(account.total(), -eighty);
+        assert!(account.is_locked());
+
+        let mut account = disputed();
+        assert_eq!(
+            chargeback(&mut account, ExcessChargebackPolicy::Clamp),
+            OpResult::Clamped
+        );
+        assert_eq!(account.held(), eighty);
+        assert_eq!(account.total(), Decimal::ZERO);
+        assert_eq!(account.available() + account.held(), account.total());
+        assert!(account.held_breakdown().is_empty());
+        assert_eq!(account.open_disputes, 0);
+        assert!(account.is_locked());
+
+        let mut account = disputed();
+        assert_eq!(
+            chargeback(&mut account, ExcessChargebackPolicy::Refuse),
+            OpResult::SkippedExceedsTotal
+        );
+        assert_eq!(account, disputed());
+        assert!(!account.is_locked());
+
+        // Nothing remains to charge back once clamped to a total of zero, the
+        // refused chargeback leaves the account open.
+        let mut account = disputed();
+        account.adjust(-twenty);
+        assert_eq!(account.total(), Decimal::ZERO);
+        assert_eq!(
+            chargeback(&mut account, ExcessChargebackPolicy::Clamp),
+            OpResult::SkippedInvalidAmount
+        );
+        assert!(!account.is_locked());
+        assert_eq!(account.frozen_at(), None);
+    }
+
+    #[test]
+    fn account_dispute_policy() {
+        let hundred = Decimal::new(100, 0);
+
+        // The deposit is disputed after all of it was withdrawn.
+        let withdrawn = || {
+            let mut account: Account = Account::new();
+            account.deposit(hundred);
+            account.deposit(Decimal::ONE);
+            account.withdraw(hundred);
+            account
+        };
+
+        let mut account = withdrawn();
+        assert_eq!(
+            account.dispute_with_policy(1, hundred, DisputePolicy::Overdraft),
+            OpResult::Applied
+        );
+        assert_eq!(account.available(), Decimal::ONE - hundred);
+        assert_eq!(account.held(), hundred);
+        assert_eq!(account.total(), Decimal::ONE);
+
+        let mut account = withdrawn();
+        assert_eq!(
+            account.dispute_with_policy(1, hundred, DisputePolicy::Refuse),
+            OpResult::SkippedInsufficientFunds
+        );
+        assert_eq!(account, withdrawn());
+        assert!(account.held_breakdown().is_empty());
+    }
+
+    #[test]
+    fn account_hold_release() {
+        let one = Decimal::ONE;
+        let ten = Decimal::TEN;
+        let mut account: Account = Account::new();
+        account.deposit(ten);
+
+        assert_eq!(
+            account.hold(1, ten + one),
+            OpResult::SkippedInsufficientFunds
+        );
+        assert_eq!(account.hold(1, ten), OpResult::Applied);
+        assert_eq!(account.available(), Decimal::ZERO);
+        assert_eq!(account.held(), ten);
+        assert_eq!(account.total(), ten);
+
+        // Releases are limited to the funds of their hold.
+        assert_eq!(account.release(2, one), OpResult::SkippedInsufficientFunds);
+        assert_eq!(
+            account.release(1, ten + one),
+            OpResult::SkippedInsufficientFunds
+        );
+        assert_eq!(account.release(1, one), OpResult::Applied);
+        assert_eq!(account.available(), one);
+        assert_eq!(account.holds(), &HashMap::from([(1, ten - one)]));
+        assert!(account.held_breakdown().is_empty());
+    }
+
+    #[test]
+    fn account_hold_release_disputed() {
+        let one = Decimal::ONE;
+        let ten = Decimal::TEN;
+        let mut account: Account = Account::new();
+        account.deposit(ten);
+        account.dispute(1, ten);
+
+        // The funds of an open dispute are neither held again nor released.
+        assert_eq!(account.hold(1, one), OpResult::SkippedDisputed);
+        assert_eq!(account.release(1, one), OpResult::SkippedDisputed);
+        assert_eq!(account.held_breakdown(), &HashMap::from([(1, ten)]));
+        assert!(account.holds().is_empty());
+    }
+
+    #[test]
+    fn client_open_dispute_count() {
+        let one = Decimal::ONE;
+        let mut client = Client::new(1);
+        client.get_mut().deposit(one);
+        client.get_mut().deposit(one);
+        client.get_mut().hold(3, one);
+        assert_eq!(client.open_dispute_count(), 0);
+
+        client.get_mut().dispute(1, one);
+        client.get_mut().dispute(2, one);
+        assert_eq!(client.open_dispute_count(), 2);
+
+        client.get_mut().resolve(1, one);
+        assert_eq!(client.open_dispute_count(), 1);
+        client.get_mut().chargeback(2, one);
+        assert_eq!(client.open_dispute_count(), 0);
+    }
+
+    #[test]
+    fn client_accessors() {
+        let mut client = Client::new(7);
+        client.get_mut().deposit(Decimal::TEN);
+        client.get_mut().dispute(1, Decimal::ONE);
+
+        assert_eq!(client.id(), 7);
+        assert_eq!(client.account(), client.get());
+        assert!(!client.is_locked());
+        assert_eq!(client.balance().available(), Decimal::new(9, 0));
+        assert_eq!(client.balance().held(), Decimal::ONE);
+        assert_eq!(client.balance().total(), Decimal::TEN);
+
+        client.get_mut().chargeback(1, Decimal::ONE);
+        assert!(client.is_locked());
+        assert_eq!(client.balance().total(), Decimal::new(9, 0));
+    }
+
+    #[test]
+    fn account_fee() {
+        let mut account: Account = Account::new();
+        account.deposit(Decimal::ONE);
+
+        assert_eq!(
+            account.fee(Decimal::TEN),
+            OpResult::SkippedInsufficientFunds
+        );
+        assert_eq!(account.fee(Decimal::ZERO), OpResult::SkippedInvalidAmount);
+        assert_eq!(account.fee(Decimal::new(5, 1)), OpResult::Applied);
+        assert_eq!(account.available(), Decimal::new(5, 1));
+        assert_eq!(
+            account.fee_with_policy(Decimal::ONE, FeePolicy::Overdraft),
+            OpResult::Applied
+        );
+        assert_eq!(account.available(), Decimal::new(-5, 1));
+        assert_eq!(account.total(), Decimal::new(-5, 1));
+    }
+
+    #[test]
+    fn account_chargeback_shortfall() {
+        let mut account: Account = Account::new();
+        account.deposit(Decimal::TEN);
+        account.dispute(1, Decimal::new(4, 0));
+
+        assert_eq!(account.chargeback(1, Decimal::new(6, 0)), OpResult::Clamped);
+        assert_eq!(account.held(), Decimal::ZERO);
+        assert_eq!(account.total(), Decimal::new(6, 0));
+        assert_eq!(account.activity().chargeback_shortfall, Decimal::new(2, 0));
+        assert!(account.is_locked());
+
+        let mut account: Account = Account::new();
+        account.deposit(Decimal::TEN);
+        assert_eq!(
+            account.chargeback(1, Decimal::ONE),
+            OpResult::SkippedInvalidAmount
+        );
+        assert_eq!(account.total(), Decimal::TEN);
+        assert!(!account.is_locked());
+    }
+
+    #[test]
+    fn account_reverse() {
+        let mut account: Account = Account::new();
+        account.deposit(Decimal::TEN);
+
+        assert_eq!(account.reverse(-Decimal::TEN), OpResult::Applied);
+        assert_eq!(
+            account.reverse(-Decimal::ONE),
+            OpResult::SkippedInsufficientFunds
+        );
+        assert_eq!(account.reverse(Decimal::ONE), OpResult::Applied);
+        assert_eq!(
+            account.reverse(Decimal::ZERO),
+            OpResult::SkippedInvalidAmount
+        );
+        assert_eq!(account.available(), Decimal::ONE);
+        assert_eq!(account.total(), Decimal::ONE);
+    }
+
+    #[test]
+    fn account_net_position() {
+        let mut account: Account = Account::new();
+        account.deposit(Decimal::TEN);
+        account.deposit(Decimal::new(5, 0));
+        account.withdraw(Decimal::new(3, 0));
+        assert_eq!(account.net_position(), Decimal::new(12, 0));
+
+        account.dispute(2, Decimal::new(5, 0));
+        assert_eq!(account.available(), Decimal::new(7, 0));
+        assert_eq!(account.net_position(), Decimal::new(12, 0));
+
+        // Refused withdrawals are not counted.
+        account.withdraw(Decimal::new(8, 0));
+        account.chargeback(2, Decimal::new(5, 0));
+        assert_eq!(account.total(), Decimal::new(7, 0));
+        assert_eq!(account.net_position(), Decimal::new(12, 0));
+    }
+
+    #[test]
+    fn account_activity_saturates() {
+        // The balance stays small, the sums of deposits and withdrawals
+        // exceed `Decimal::MAX`.
+        let amount = "7000000000000000000000000000".parse::<Decimal>().unwrap();
+        let mut account: Account = Account::new();
+        for _ in 0..12 {
+            assert_eq!(account.deposit(amount), OpResult::Applied);
+            assert_eq!(account.withdraw(amount - Decimal::ONE), OpResult::Applied);
+        }
+
+        assert_eq!(account.total(), Decimal::new(12, 0));
+        assert_eq!(account.activity().deposited, Decimal::MAX);
+        assert_eq!(account.activity().withdrawn, Decimal::MAX);
+        assert_eq!(account.activity().deposits, 12);
+        assert_eq!(account.net_position(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn client_activity_summary() {
+        let mut client = Client::new(1);
+        let account = client.get_mut();
+        account.deposit(Decimal::TEN);
+        account.deposit(Decimal::new(5, 0));
+        account.withdraw(Decimal::new(3, 0));
+        account.fee(Decimal::ONE);
+        account.dispute(2, Decimal::new(5, 0));
+        account.resolve(2, Decimal::new(5, 0));
+        // Refused withdrawals are not counted.
+        account.withdraw(Decimal::new(100, 0));
+
+        let summary = client.activity_summary();
+        assert_eq!(summary.len(), 5);
+        assert_eq!(summary[&TransactionKind::Deposit], 2);
+        assert_eq!(summary[&TransactionKind::Withdrawal], 1);
+        assert_eq!(summary[&TransactionKind::Fee], 1);
+        assert_eq!(summary[&TransactionKind::Dispute], 1);
+        assert_eq!(summary[&TransactionKind::Resolve], 1);
+        assert!(!summary.contains_key(&TransactionKind::Chargeback));
+    }
+
+    #[test]
+    fn account_undo() {
+        let mut account: Account = Account::new();
+        account.deposit(Decimal::TEN);
+        account.dispute(1, Decimal::new(4, 0));
+        account.resolve(1, Decimal::ONE);
+
+        assert_eq!(
+            account.undo(&TransactionKind::Resolve, 1, Decimal::ONE),
+            OpResult::Applied
+        );
+        assert_eq!(account.held(), Decimal::new(4, 0));
+        assert_eq!(
+            account.undo(&TransactionKind::Dispute, 1, Decimal::new(4, 0)),
+            OpResult::Applied
+        );
+        assert_eq!(account.held(), Decimal::ZERO);
+        assert_eq!(account.open_disputes, 0);
+        assert!(account.held_breakdown().is_empty());
+        assert_eq!(
+            account.undo(&TransactionKind::Deposit, 0, Decimal::TEN),
+            OpResult::Applied
+        );
+        assert_eq!(account.total(), Decimal::ZERO);
+        assert_eq!(
+            account.undo(&TransactionKind::Chargeback, 1, Decimal::ONE),
+            OpResult::SkippedIrreversible
+        );
+    }
+
+    #[test]
+    fn client_frozen_at() {
+        let mut client = Client::new(1);
+        client.get_mut().deposit(Decimal::TEN);
+        client.get_mut().dispute(3, Decimal::ONE);
+        assert_eq!(client.frozen_at(), None);
+
+        client.get_mut().chargeback(3, Decimal::ONE);
+        assert_eq!(client.frozen_at(), Some(3));
+    }
+
+    #[test]
+    fn account_adjust() {
+        let one = Decimal::ONE;
+        let ten = Decimal::TEN;
+        let mut account = Account::new();
+
+        assert_eq!(account.adjust(ten), OpResult::Applied);
+        assert_eq!(account.adjust(-ten - one), OpResult::Applied);
+        assert_eq!(
+            account.adjust(Decimal::ZERO),
+            OpResult::SkippedInvalidAmount
+        );
+        assert_eq!(
+            account,
+            Account {
+                inner: AccountInner::Open {
+                    balance: Balance {
+                        available: -one,
+                        held: Decimal::ZERO,
+                        total: -one,
+                    }
+                },
+                held_by_tx: HashMap::new(),
+                holds: HashMap::new(),
+                open_disputes: 0,
+                activity: Activity::default(),
+                frozen_at_tx: None,
+            }
+        );
+
+        account.dispute(1, one);
+        account.chargeback(1, one);
+        assert_eq!(account.adjust(ten), OpResult::SkippedLocked);
+    }
+
+    #[test]
+    fn account_transfer_to() {
+        let zero = Decimal::ZERO;
+        let one = Decimal::ONE;
+        let ten = Decimal::TEN;
+        let mut from: Account = Account::new();
+        let mut to = Account::new();
+        from.deposit(ten);
+
+        assert_eq!(from.transfer_to(&mut to, ten), OpResult::Applied);
+        assert_eq!((from.available(), from.total()), (zero, zero));
+        assert_eq!((to.available(), to.total()), (ten, ten));
+
+        assert_eq!(
+            from.transfer_to(&mut to, one),
+            OpResult::SkippedInsufficientFunds
+        );
+        to.dispute(1, one);
+        to.chargeback(1, one);
+        assert_eq!(to.transfer_to(&mut from, one), OpResult::SkippedLocked);
+    }
+
+    #[test]
+    fn balance_transfer_to() {
+        let one = Decimal::ONE;
+        let ten = Decimal::TEN;
+        let mut from = Balance::new();
+        let mut to = Balance::new();
+        from.deposit(ten);
+
+        let amount = from.transfer_to(one).unwrap();
+        to.deposit(amount);
+        assert_eq!(
+            from,
+            Balance {
+                available: ten - one,
+                held: Decimal::ZERO,
+                total: ten - one,
+            }
+        );
+        assert_eq!(
+            to,
+            Balance {
+                available: one,
+                held: Decimal::ZERO,
+                total: one,
+            }
+        );
+
+        // Insufficient funds leave the balance untouched.
+        assert_eq!(to.transfer_to(ten), None);
+        assert_eq!(to.transfer_to(Decimal::NEGATIVE_ONE), None);
+        assert_eq!(
+            to,
+            Balance {
+                available: one,
+                held: Decimal::ZERO,
+                total: one,
+            }
+        );
+    }
+
+    #[test]
+    fn balance_to_minor_units() {
+        let mut balance: Balance = Balance::new();
+        balance.deposit(Decimal::new(15, 1));
+        balance.dispute(Decimal::new(5, 3));
+
+        assert_eq!(balance.to_minor_units(2), Some((150, 0, 150)));
+        assert_eq!(balance.to_minor_units(4), Some((14950, 50, 15000)));
+        assert_eq!(balance.to_minor_units(0), Some((1, 0, 2)));
+        assert_eq!(balance.to_minor_units(30), None);
+    }
+
+    #[test]
+    fn balance_scales() {
+        let mut balance: Balance = Balance::new();
+        assert_eq!(balance.available_scale(), DEFAULT_SCALE);
+        assert_eq!(balance.held_scale(), DEFAULT_SCALE);
+        assert_eq!(balance.total_scale(), DEFAULT_SCALE);
+
+        // Arithmetic takes the scale of the amounts, including held funds
+        // which return to zero.
+        balance.deposit(Decimal::new(15, 1));
+        balance.dispute(Decimal::new(15, 1));
+        balance.resolve(Decimal::new(15, 1));
+        assert_eq!(balance.available_scale(), 1);
+        assert_eq!(balance.held_scale(), 1);
+        assert_eq!(balance.total_scale(), 1);
+
+        let mut account: Account = Account::new();
+        assert_eq!(
+            account.scales(),
+            (DEFAULT_SCALE, DEFAULT_SCALE, DEFAULT_SCALE)
+        );
+        account.deposit(Decimal::new(15, 1));
+        assert_eq!(account.scales(), (1, DEFAULT_SCALE, 1));
+    }
+
+    #[test]
+    fn client_row_minor_units() {
+        let mut client = Client::new(1);
+        client.get_mut().deposit(Decimal::new(15, 1));
+        let options = OutputOptions {
+            minor_units: Some(2),
+            ..OutputOptions::default()
+        };
+
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        wtr.serialize(client.row(&options)).unwrap();
+        wtr.serialize(&client).unwrap();
+        assert_eq!(
+            String::from_utf8(wtr.into_inner().unwrap()).unwrap(),
+            "client,available,held,total,locked\n1,150,0,150,false\n1,1.5,0.0000,1.5,false\n"
+        );
+    }
+
+    #[test]
+    fn client_row_fixed_width() {
+        let mut client = Client::new(1);
+        client.get_mut().deposit(Decimal::new(15, 1));
+        let options = OutputOptions {
+            scale: Some(4),
+            fixed_width: Some(14),
+            ..OutputOptions::default()
+        };
+
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        wtr.serialize(client.row(&options)).unwrap();
+        assert_eq!(
+            String::from_utf8(wtr.into_inner().unwrap()).unwrap(),
+            "client,available,held,total,locked\n\
+             1,000000001.5000,000000000.0000,000000001.5000,false\n"
+        );
+    }
+
+    #[test]
+    fn client_row_computed_total_overflow() {
+        let client = Client::from_snapshot_unchecked(ClientSnapshot {
+            client: 1,
+            available: Decimal::MAX,
+            held: Decimal::MAX,
+            total: Decimal::MAX,
+            locked: false,
+            flagged: false,
+        });
+        let options = OutputOptions {
+            computed_total: true,
+            ..OutputOptions::default()
+        };
+
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        assert!(wtr.serialize(client.row(&options)).is_err());
+        // The computed total is only summed when written.
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        assert!(wtr.serialize(&client).is_ok());
+    }
+
+    #[test]
+    fn zero_padded() {
+        assert_eq!(super::zero_padded(Decimal::new(15, 1), 6), "0001.5");
+        assert_eq!(super::zero_padded(Decimal::new(-15, 1), 6), "-001.5");
+        assert_eq!(super::zero_padded(Decimal::new(12345, 1), 3), "1234.5");
     }
 
     #[test]
@@ -765,10 +3026,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn balance_sum() {
+        let mut clients = [Client::new(1), Client::new(2), Client::new(3)];
+        clients[0].get_mut().deposit(Decimal::ONE);
+        clients[1].get_mut().deposit(Decimal::TEN);
+        clients[1].get_mut().dispute(1, Decimal::new(25, 1));
+        clients[2].get_mut().deposit(Decimal::new(5, 1));
+
+        let total: Balance = clients.iter().map(Client::balance).sum();
+        assert_eq!(total.available(), Decimal::new(90, 1));
+        assert_eq!(total.held(), Decimal::new(25, 1));
+        assert_eq!(total.total(), Decimal::new(115, 1));
+    }
+
     #[test]
     fn balance_new() {
         let zero = Decimal::ZERO;
-        let balance = Balance::new();
+        let balance: Balance = Balance::new();
         assert_eq!(balance.available, zero);
         assert_eq!(balance.held, zero);
         assert_eq!(balance.total, zero);
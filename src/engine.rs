@@ -0,0 +1,1541 @@
+//! Transaction processing engine.
+//!
+//! `Engine` owns the client accounts along with an in-memory index of
+//! deposits so disputes never need to re-read the records. Options are set
+//! with consuming builder methods before processing any records.
+
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::client::{
+    Account, AccountPolicy, Balance, ChargebackPolicy, Client, DisputePolicy,
+    ExcessChargebackPolicy, FeePolicy, LockedZeroBalance, OpResult, OutputOptions, Rounding,
+};
+use crate::error::KoncordError;
+use crate::money::Money;
+use crate::transaction::{
+    dispatch, Outcome, Received, Record, Referenced, Tracker, Transaction, TransactionKind,
+};
+use crate::{deserialize_records, Lookup, ReaderConfig, DEFAULT_SCALE};
+
+/// Counters collected while processing records.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct RunStats {
+    /// Number of records processed.
+    pub records: usize,
+    /// Transactions applied to an account.
+    pub applied: usize,
+    /// Transactions refused by the account, see `OpResult`.
+    pub skipped: usize,
+    /// Deposits and withdrawals refused for a zero amount.
+    pub zero_amounts: usize,
+    /// Disputes refused because the disputed transaction fell outside the
+    /// dispute window.
+    pub disputes_expired: usize,
+    /// Disputes refused because their client already had
+    /// `Engine::max_open_disputes_per_client` disputes open.
+    pub disputes_over_limit: usize,
+    /// Disputes refused because their transaction has an open dispute, or
+    /// under `Engine::allow_redispute(false)` was disputed before.
+    pub redisputes_refused: usize,
+    /// Withdrawals refused under `Engine::reject_dust` for leaving more
+    /// decimal places than the scale.
+    pub dust_withdrawals: usize,
+    /// Records refused for a timestamp earlier than the last record of the
+    /// same client.
+    pub out_of_order: usize,
+    /// Records skipped for an unknown transaction type.
+    pub unknown_kinds: usize,
+    /// Corrections refused as the deposit or withdrawal they refer to does
+    /// not exist for their client.
+    pub orphan_corrections: usize,
+    /// Records refused for an amount above `Engine::max_amount`.
+    pub over_max_amount: usize,
+    /// Records refused because their client exceeded
+    /// `Engine::max_tx_per_client`.
+    pub capped: usize,
+    /// Disputes refused under `DisputePolicy::Refuse` because the disputed
+    /// deposit is no longer fully available, included in `skipped`.
+    pub disputes_refused: usize,
+    /// Chargebacks of more than the total funds of the account, handled as
+    /// set by `Engine::excess_chargeback_policy`.
+    pub excess_chargebacks: usize,
+    /// Chargebacks ignored as their transaction has no open dispute, such as
+    /// one already resolved in full.
+    pub orphan_chargebacks: usize,
+    /// Deposits and withdrawals reusing the Transaction ID of an earlier
+    /// withdrawal or deposit, processed but likely malformed input as
+    /// disputes and reversals only find the latest of them.
+    pub reused_tx: usize,
+    /// Funds held across all accounts after processing, non-zero when
+    /// disputes or holds were left open. Saturates at `Decimal::MAX` should
+    /// the funds held by every account together exceed it.
+    pub total_held_remaining: Decimal,
+    /// Clients whose available plus held funds are not their total after
+    /// processing, sorted by Client ID. Expected only with
+    /// `Engine::saturating`, otherwise a bug in an account operation.
+    pub invariant_violations: Vec<u16>,
+}
+
+impl RunStats {
+    /// Returns the number of transactions refused for any reason.
+    pub fn rejected(&self) -> usize {
+        self.skipped
+            + self.zero_amounts
+            + self.disputes_expired
+            + self.disputes_over_limit
+            + self.redisputes_refused
+            + self.dust_withdrawals
+            + self.out_of_order
+            + self.unknown_kinds
+            + self.orphan_corrections
+            + self.over_max_amount
+            + self.capped
+    }
+
+    // Add the counters of `other`, invariant violations and the held funds
+    // remaining are found again once merged.
+    fn merge(&mut self, other: &RunStats) {
+        self.records += other.records;
+        self.applied += other.applied;
+        self.skipped += other.skipped;
+        self.zero_amounts += other.zero_amounts;
+        self.disputes_expired += other.disputes_expired;
+        self.disputes_over_limit += other.disputes_over_limit;
+        self.redisputes_refused += other.redisputes_refused;
+        self.dust_withdrawals += other.dust_withdrawals;
+        self.out_of_order += other.out_of_order;
+        self.unknown_kinds += other.unknown_kinds;
+        self.orphan_corrections += other.orphan_corrections;
+        self.over_max_amount += other.over_max_amount;
+        self.capped += other.capped;
+        self.excess_chargebacks += other.excess_chargebacks;
+        self.orphan_chargebacks += other.orphan_chargebacks;
+        self.reused_tx += other.reused_tx;
+        self.disputes_refused += other.disputes_refused;
+    }
+
+    // Returns the reason a record was refused since the counters were
+    // `before`, if it was. Records refused by the account give the outcome
+    // `result` of the operation.
+    fn rejection_since(&self, before: &RunStats, result: Option<OpResult>) -> Option<&'static str> {
+        if self.skipped > before.skipped {
+            return Some(result.map_or("skipped", |result| result.as_str()));
+        }
+        [
+            (self.zero_amounts, before.zero_amounts, "zero_amount"),
+            (
+                self.disputes_expired,
+                before.disputes_expired,
+                "dispute_expired",
+            ),
+            (
+                self.disputes_over_limit,
+                before.disputes_over_limit,
+                "disputes_over_limit",
+            ),
+            (
+                self.redisputes_refused,
+                before.redisputes_refused,
+                "redispute_refused",
+            ),
+            (self.dust_withdrawals, before.dust_withdrawals, "dust"),
+            (self.out_of_order, before.out_of_order, "out_of_order"),
+            (self.unknown_kinds, before.unknown_kinds, "unknown_kind"),
+            (
+                self.orphan_corrections,
+                before.orphan_corrections,
+                "orphan_correction",
+            ),
+            (
+                self.over_max_amount,
+                before.over_max_amount,
+                "over_max_amount",
+            ),
+            (self.capped, before.capped, "capped"),
+        ]
+        .into_iter()
+        .find(|(after, before, _)| after > before)
+        .map(|(_, _, reason)| reason)
+    }
+
+    // Count the outcome of an account operation.
+    fn count(&mut self, result: OpResult) {
+        match result {
+            OpResult::Applied | OpResult::Clamped => self.applied += 1,
+            _ => self.skipped += 1,
+        }
+    }
+}
+
+/// A record as kept in the ledger for audit.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LedgerEntry {
+    /// Client ID.
+    pub client: u16,
+    /// Transaction ID.
+    pub tx: u32,
+    /// Transaction type.
+    pub kind: TransactionKind,
+    /// Transaction amount as recorded.
+    pub amount: Option<Decimal>,
+    /// Free text memo of the record.
+    pub description: Option<String>,
+}
+
+impl From<&Record> for LedgerEntry {
+    fn from(record: &Record) -> Self {
+        LedgerEntry {
+            client: record.client_id(),
+            tx: record.tx(),
+            kind: record.kind().clone(),
+            amount: record.amount(),
+            description: record.description().map(str::to_string),
+        }
+    }
+}
+
+/// The balance of a client before and after an applied transaction, kept for
+/// audit.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BalanceChange<M: Money = Decimal> {
+    /// Client ID.
+    pub client: u16,
+    /// Transaction ID.
+    pub tx: u32,
+    /// Transaction type.
+    pub kind: TransactionKind,
+    /// Balance before the transaction was applied.
+    pub before: Balance<M>,
+    /// Balance after the transaction was applied.
+    pub after: Balance<M>,
+}
+
+/// A record refused while processing, kept for diagnostics.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Warning {
+    /// Client ID.
+    pub client: u16,
+    /// Transaction ID.
+    pub tx: u32,
+    /// Why the record was refused in snake case, such as
+    /// `insufficient_funds`, see `OpResult::as_str`.
+    pub reason: &'static str,
+}
+
+/// A dispute left open by processing, written by
+/// `Engine::write_open_disputes` to resume with `Engine::with_open_disputes`.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct OpenDispute {
+    /// Disputed Transaction ID.
+    pub tx: u32,
+    /// Client ID of the disputed account.
+    pub client: u16,
+    /// Funds held by the dispute.
+    pub amount: Decimal,
+}
+
+/// Processes transaction records against client accounts.
+///
+/// New clients are created with zero balances as new Client IDs are
+/// encountered. Balances are kept in `M`, `Decimal` unless created with
+/// `Engine::with_money`.
+#[derive(Debug, Default, Clone)]
+pub struct Engine<M: Money = Decimal> {
+    clients: Clients<M>,
+    deposits: IdMap<u32, Deposit>,
+    // Applied withdrawals, indexed as deposits for reversals.
+    withdrawals: IdMap<u32, Deposit>,
+    // Exposed by `open_disputes` so keeps the default hasher.
+    disputes: HashMap<u32, Decimal>,
+    // Number of disputes applied to each Transaction ID.
+    dispute_counts: IdMap<u32, usize>,
+    // Referenced transactions are read from the records as `run` does, rather
+    // than from the indexes, when set.
+    lookup: Option<Lookup>,
+    stats: RunStats,
+    timestamps: Option<IdMap<u16, u64>>,
+    tx_counts: IdMap<u16, usize>,
+    max_tx_per_client: Option<usize>,
+    ledger: Option<Vec<LedgerEntry>>,
+    balance_changes: Option<Vec<BalanceChange<M>>>,
+    warnings: Option<Vec<Warning>>,
+    // Outcome of the last account operation, the reason of refused records.
+    last_result: Option<OpResult>,
+    max_dispute_age: Option<usize>,
+    max_open_disputes: Option<usize>,
+    max_amount: Option<Decimal>,
+    allow_zero_amount: bool,
+    refuse_redispute: bool,
+    strict: bool,
+    fail_on_held_remaining: bool,
+    reject_dust: bool,
+    coalesce_deposits: bool,
+    currency: Option<char>,
+    pending: Option<PendingDeposit>,
+    // Incremented as records or clients change the engine, so `commit`
+    // refuses batches staged before the change.
+    generation: u64,
+    policy: AccountPolicy,
+    output: OutputOptions,
+}
+
+// Hash builder of the maps keyed by Client or Transaction ID.
+//
+// The IDs are small integers, with the `fast-hash` feature they are hashed
+// with FxHash rather than SipHash which resists collisions from untrusted
+// input.
+#[cfg(feature = "fast-hash")]
+type IdHasher = rustc_hash::FxBuildHasher;
+#[cfg(not(feature = "fast-hash"))]
+type IdHasher = std::collections::hash_map::RandomState;
+
+type IdMap<K, V> = HashMap<K, V, IdHasher>;
+
+// Client storage.
+//
+// Clients are hashed by default, the ordered map iterates in Client ID order
+// at the cost of slower lookups.
+#[derive(Debug, Clone)]
+enum Clients<M: Money> {
+    Hashed(IdMap<u16, Client<M>>),
+    Ordered(BTreeMap<u16, Client<M>>),
+}
+
+// Clients are allocated as they are seen, an engine is cloned by `stage` and
+// built per thread by `run_parallel` so must be cheap to create.
+impl<M: Money> Default for Clients<M> {
+    fn default() -> Self {
+        Clients::Hashed(IdMap::default())
+    }
+}
+
+impl<M: Money> Clients<M> {
+    // Returns the client with `id`, creating it with a zero balance if new.
+    fn entry(&mut self, id: u16, scale: Option<u32>) -> &mut Client<M> {
+        let new = || Client::zeroed(id, scale.unwrap_or(DEFAULT_SCALE));
+        match self {
+            Clients::Hashed(clients) => clients.entry(id).or_insert_with(new),
+            Clients::Ordered(clients) => clients.entry(id).or_insert_with(new),
+        }
+    }
+
+    fn get(&self, id: u16) -> Option<&Client<M>> {
+        match self {
+            Clients::Hashed(clients) => clients.get(&id),
+            Clients::Ordered(clients) => clients.get(&id),
+        }
+    }
+
+    fn values(&self) -> Box<dyn Iterator<Item = &Client<M>> + '_> {
+        match self {
+            Clients::Hashed(clients) => Box::new(clients.values()),
+            Clients::Ordered(clients) => Box::new(clients.values()),
+        }
+    }
+
+    // Add `clients`, replacing any with the same Client ID.
+    fn extend(&mut self, clients: HashMap<u16, Client<M>>) {
+        match self {
+            Clients::Hashed(existing) => existing.extend(clients),
+            Clients::Ordered(existing) => existing.extend(clients),
+        }
+    }
+
+    fn into_map(self) -> HashMap<u16, Client<M>> {
+        match self {
+            #[cfg(not(feature = "fast-hash"))]
+            Clients::Hashed(clients) => clients,
+            #[cfg(feature = "fast-hash")]
+            Clients::Hashed(clients) => clients.into_iter().collect(),
+            Clients::Ordered(clients) => clients.into_iter().collect(),
+        }
+    }
+}
+
+// A deposit as recorded in the index for dispute lookups.
+#[derive(Debug, Clone)]
+struct Deposit {
+    client: u16,
+    amount: Decimal,
+    position: usize,
+}
+
+/// A batch of transaction records processed by `Engine::stage` without
+/// errors, not yet applied with `Engine::commit`.
+#[derive(Debug)]
+pub struct StagedBatch<M: Money = Decimal> {
+    engine: Engine<M>,
+    // Generation of the engine the batch was staged on.
+    staged_on: u64,
+}
+
+impl<M: Money> StagedBatch<M> {
+    /// Returns the statistics of the engine once the batch is applied.
+    pub fn stats(&self) -> &RunStats {
+        &self.engine.stats
+    }
+}
+
+// Net of consecutive deposits to a client not yet applied.
+#[derive(Debug, Clone)]
+struct PendingDeposit {
+    client: u16,
+    amount: Decimal,
+    count: usize,
+}
+
+// The indexes of an `Engine` tracking transactions for `dispatch`.
+struct Indexes<'a> {
+    deposits: &'a mut IdMap<u32, Deposit>,
+    withdrawals: &'a mut IdMap<u32, Deposit>,
+    disputes: &'a mut HashMap<u32, Decimal>,
+    dispute_counts: &'a mut IdMap<u32, usize>,
+    lookup: Option<&'a mut Lookup>,
+}
+
+impl Tracker for Indexes<'_> {
+    fn referenced(
+        &mut self,
+        kind: &TransactionKind,
+        tx: u32,
+        id: u16,
+    ) -> Result<Option<Referenced>, Box<dyn Error>> {
+        if let Some(lookup) = &self.lookup {
+            return lookup.referenced(kind, tx, id);
+        }
+        let deposit = self
+            .deposits
+            .get(&tx)
+            .map(|deposit| (TransactionKind::Deposit, deposit));
+        let withdrawal = self
+            .withdrawals
+            .get(&tx)
+            .map(|withdrawal| (TransactionKind::Withdrawal, withdrawal));
+        let owned = |(_, original): &(TransactionKind, &Deposit)| original.client == id;
+        // Only deposits are disputed, a reused Transaction ID reverses the
+        // deposit.
+        let original = match kind {
+            TransactionKind::Dispute => deposit.filter(owned),
+            TransactionKind::Correction => deposit.filter(owned).or(withdrawal.filter(owned)),
+            _ => deposit.or(withdrawal).filter(owned),
+        };
+
+        Ok(original.map(|(kind, original)| Referenced {
+            kind,
+            amount: Some(original.amount),
+        }))
+    }
+
+    fn disputes(&mut self) -> &mut HashMap<u32, Decimal> {
+        self.disputes
+    }
+
+    fn applied(&mut self, kind: &TransactionKind, tx: u32) {
+        match kind {
+            TransactionKind::Dispute => *self.dispute_counts.entry(tx).or_default() += 1,
+            // Reversed transactions can be neither disputed nor reversed
+            // again.
+            TransactionKind::Reversal if self.deposits.remove(&tx).is_none() => {
+                self.withdrawals.remove(&tx);
+            }
+            _ => {}
+        }
+        if let Some(lookup) = &mut self.lookup {
+            lookup.applied(kind, tx);
+        }
+    }
+}
+
+impl Engine {
+    /// Create a new `Engine` without any clients.
+    pub fn new() -> Self {
+        Engine::default()
+    }
+
+    /// Create a new `Engine` without any clients keeping balances in `M`,
+    /// such as `MinorUnits`.
+    ///
+    /// Amounts are read as `Decimal` and converted as applied, amounts `M`
+    /// cannot represent are refused as invalid.
+    pub fn with_money<M: Money>() -> Engine<M> {
+        Engine::default()
+    }
+}
+
+impl<M: Money> Engine<M> {
+    /// Refuse disputes of transactions more than `records` records old.
+    ///
+    /// The age of a transaction is the number of records processed since it,
+    /// so with a window of `1` only the record immediately following a
+    /// deposit may dispute it.
+    pub fn max_dispute_age(mut self, records: usize) -> Self {
+        self.max_dispute_age = Some(records);
+        self
+    }
+
+    /// Fail `process` with `KoncordError::HeldRemaining` if any funds remain
+    /// held once all records are processed.
+    ///
+    /// Held funds are only left by disputes or holds that were never closed,
+    /// the total is counted in `RunStats::total_held_remaining` regardless.
+    pub fn fail_on_held_remaining(mut self, fail: bool) -> Self {
+        self.fail_on_held_remaining = fail;
+        self
+    }
+
+    /// Refuse new disputes of a client with `max` disputes already open.
+    ///
+    /// Partially resolved disputes remain open and count towards the limit,
+    /// refused disputes are counted in `RunStats`.
+    pub fn max_open_disputes_per_client(mut self, max: usize) -> Self {
+        self.max_open_disputes = Some(max);
+        self
+    }
+
+    /// Allow disputing a transaction again once its dispute was resolved,
+    /// allowed by default.
+    ///
+    /// A resolved deposit is available again so a new dispute holds it
+    /// again, when not allowed any dispute of a transaction disputed before
+    /// is refused and counted in `RunStats`. A transaction whose dispute is
+    /// still open is never disputed again either way.
+    pub fn allow_redispute(mut self, allow: bool) -> Self {
+        self.refuse_redispute = !allow;
+        self
+    }
+
+    /// Look up the transactions referenced by disputes, corrections and
+    /// reversals by reading the records at `records_path` as `run` does,
+    /// rather than in the in-memory index.
+    ///
+    /// `records_path` must hold the records processed. Referenced
+    /// transactions are then found exactly as `run` finds them, so a dispute
+    /// of an absent transaction holds its own amount.
+    pub fn lookup_path(mut self, records_path: &str) -> Self {
+        self.lookup = Some(Lookup::new(records_path, &ReaderConfig::default()));
+        self
+    }
+
+    /// Refuse records with an amount above `max`, or below `-max` for signed
+    /// adjustments.
+    ///
+    /// Guards against typos near `Decimal::MAX` overflowing a balance on a
+    /// later transaction, refused records are counted in `RunStats`.
+    pub fn max_amount(mut self, max: Decimal) -> Self {
+        self.max_amount = Some(max);
+        self
+    }
+
+    /// Accept deposits and withdrawals with a zero amount.
+    ///
+    /// Zero amount transactions are refused and counted by default. When
+    /// allowed they are recorded as applied, and deposits may be disputed,
+    /// without changing any balance.
+    pub fn allow_zero_amount(mut self, allow: bool) -> Self {
+        self.allow_zero_amount = allow;
+        self
+    }
+
+    /// Accept amounts prefixed with the currency `symbol`, such as `$1.00`.
+    ///
+    /// A leading `+` is always accepted.
+    pub fn currency_symbol(mut self, symbol: char) -> Self {
+        self.currency = Some(symbol);
+        self
+    }
+
+    // Returns the currency symbol accepted before amounts.
+    pub(crate) fn currency(&self) -> Option<char> {
+        self.currency
+    }
+
+    /// Coalesce consecutive deposits to the same client into one deposit.
+    ///
+    /// Dense streams of small deposits are applied as a single net deposit,
+    /// each deposit is still recorded under its own Transaction ID so it may
+    /// be disputed. Balances and statistics are identical to processing each
+    /// deposit on its own.
+    pub fn coalesce_deposits(mut self, coalesce: bool) -> Self {
+        self.coalesce_deposits = coalesce;
+        self
+    }
+
+    /// Refuse records with a timestamp earlier than the last record of the
+    /// same client.
+    ///
+    /// Refused records are counted in `RunStats`, records without a
+    /// timestamp are always accepted.
+    pub fn reject_out_of_order(mut self, reject: bool) -> Self {
+        self.timestamps = reject.then(IdMap::default);
+        self
+    }
+
+    /// Refuse records of a client after its first `max` transactions.
+    ///
+    /// Refused records are counted in `RunStats`, records of unknown types
+    /// do not count towards the cap.
+    pub fn max_tx_per_client(mut self, max: usize) -> Self {
+        self.max_tx_per_client = Some(max);
+        self
+    }
+
+    /// Reject disputes, resolves and chargebacks with an amount.
+    ///
+    /// Their amount is looked up so one in the records signals a malformed
+    /// file, processing fails with `KoncordError::UnexpectedAmount`. This
+    /// also rejects partial resolves.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Set what happens to an account after a chargeback.
+    ///
+    /// Accounts are locked by default. With `ChargebackPolicy::Flag` they
+    /// remain usable and are written with a `flagged` column for review.
+    pub fn chargeback_policy(mut self, policy: ChargebackPolicy) -> Self {
+        self.policy.chargeback = policy;
+        self.output.flagged = policy == ChargebackPolicy::Flag;
+        self
+    }
+
+    /// Set what happens to a chargeback exceeding the total funds.
+    ///
+    /// The full amount is charged back by default. Excess chargebacks are
+    /// counted in `RunStats` under every policy.
+    pub fn excess_chargeback_policy(mut self, policy: ExcessChargebackPolicy) -> Self {
+        self.policy.excess_chargeback = policy;
+        self
+    }
+
+    /// Set what happens to a dispute exceeding the available funds.
+    ///
+    /// The full amount is held by default, leaving negative available funds.
+    pub fn dispute_policy(mut self, policy: DisputePolicy) -> Self {
+        self.policy.dispute = policy;
+        self
+    }
+
+    /// Set what happens to a fee exceeding the available funds.
+    ///
+    /// Fees are refused like withdrawals by default.
+    pub fn fee_policy(mut self, policy: FeePolicy) -> Self {
+        self.policy.fee = policy;
+        self
+    }
+
+    /// Round deposit and withdrawal amounts to the balance scale with
+    /// `rounding` before applying them.
+    ///
+    /// Amounts are applied as recorded by default. Amounts rounding to zero
+    /// are refused as zero amounts.
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.policy.rounding = rounding;
+        self
+    }
+
+    /// Keep a ledger of every record processed, see `ledger`.
+    pub fn ledger_enabled(mut self, enabled: bool) -> Self {
+        self.ledger = enabled.then(Vec::new);
+        self
+    }
+
+    /// Keep the balance before and after every applied transaction, see
+    /// `balance_changes`.
+    ///
+    /// Coalesced deposits are applied together so are not kept.
+    pub fn balance_changes_enabled(mut self, enabled: bool) -> Self {
+        self.balance_changes = enabled.then(Vec::new);
+        self
+    }
+
+    /// Keep a `Warning` for every record refused, see `warnings`.
+    pub fn warnings_enabled(mut self, enabled: bool) -> Self {
+        self.warnings = enabled.then(Vec::new);
+        self
+    }
+
+    /// Refuse withdrawals leaving a balance with more decimal places than
+    /// `scale`.
+    ///
+    /// Such residue, like `0.00001` at four places, points to a precision bug
+    /// upstream so inputs must respect the configured precision. Refused
+    /// withdrawals are counted in `RunStats`.
+    pub fn reject_dust(mut self, reject: bool) -> Self {
+        self.reject_dust = reject;
+        self
+    }
+
+    /// Clamp balances at zero after every operation.
+    ///
+    /// Guarantees non-negative available, held and total funds even when
+    /// corrupt records over-subtract, at the cost of available plus held no
+    /// longer equalling total.
+    pub fn saturating(mut self, saturating: bool) -> Self {
+        self.policy.saturating = saturating;
+        self
+    }
+
+    /// Write balances as integer counts of minor units at `scale`.
+    pub fn minor_units(mut self, scale: u32) -> Self {
+        self.output.minor_units = Some(scale);
+        self
+    }
+
+    /// Create clients and write balances at `scale` decimal places.
+    ///
+    /// Balances are rounded or padded to `scale` when written, for example
+    /// `1.5` is written as `1.50` at a scale of `2`.
+    pub fn with_scale(mut self, scale: u32) -> Self {
+        self.output.scale = Some(scale);
+        self.policy.scale = Some(scale);
+        self
+    }
+
+    /// Returns the number of decimal places balances are written at.
+    pub fn scale(&self) -> u32 {
+        self.output.scale.unwrap_or(DEFAULT_SCALE)
+    }
+
+    /// Write nothing rather than the header row when there are no clients.
+    pub fn omit_empty_header(mut self, omit: bool) -> Self {
+        self.output.omit_empty_header = omit;
+        self
+    }
+
+    /// Write balances zero-padded to at least `width` characters.
+    ///
+    /// For fixed-width consumers, for example `1.5` is written as
+    /// `000000001.5000` at a width of `14` and the default scale.
+    pub fn fixed_width(mut self, width: usize) -> Self {
+        self.output.fixed_width = Some(width);
+        self
+    }
+
+    /// Write a trailing `computed_total` column of available plus held funds.
+    ///
+    /// Lets consumers verify `total` independently of the engine.
+    pub fn computed_total(mut self, computed_total: bool) -> Self {
+        self.output.computed_total = computed_total;
+        self
+    }
+
+    /// Write only clients with records processed by this `Engine`.
+    ///
+    /// Clients carried over with `with_clients` without any records since are
+    /// left out of `write`, so incremental runs output only the clients that
+    /// changed. Records of unknown types do not count as activity.
+    pub fn only_active(mut self, only_active: bool) -> Self {
+        self.output.only_active = only_active;
+        self
+    }
+
+    /// Output locked accounts with a zero balance as `treatment` directs.
+    ///
+    /// By default they are written as any other locked account.
+    pub fn locked_zero_balance(mut self, treatment: LockedZeroBalance) -> Self {
+        self.output.locked_zero_balance = treatment;
+        self
+    }
+
+    /// Process records against prior `clients`, for example loaded from a
+    /// previous run's output with `load_clients`.
+    ///
+    /// Replaces any client already seen with the same Client ID.
+    pub fn with_clients(mut self, clients: HashMap<u16, Client<M>>) -> Self {
+        self.clients.extend(clients);
+        self.generation += 1;
+        self
+    }
+
+    /// Resume the open `disputes` of a previous run, see
+    /// `write_open_disputes`.
+    ///
+    /// Each dispute is registered on the account of its client, whose held
+    /// funds already include it, so it counts towards
+    /// `max_open_disputes_per_client` and may be resolved or charged back.
+    /// Call after `with_clients` as it replaces the accounts.
+    pub fn with_open_disputes<I>(mut self, disputes: I) -> Self
+    where
+        I: IntoIterator<Item = OpenDispute>,
+    {
+        for dispute in disputes {
+            self.clients
+                .entry(dispute.client, self.output.scale)
+                .get_mut()
+                .restore_dispute(dispute.tx, dispute.amount);
+            self.disputes.insert(dispute.tx, dispute.amount);
+        }
+        self.generation += 1;
+        self
+    }
+
+    /// Store clients ordered by Client ID.
+    ///
+    /// `clients` then iterates in Client ID order so output needs no sorting.
+    pub fn ordered_clients(mut self, ordered: bool) -> Self {
+        self.clients = match (self.clients, ordered) {
+            (Clients::Hashed(clients), true) => Clients::Ordered(clients.into_iter().collect()),
+            (Clients::Ordered(clients), false) => Clients::Hashed(clients.into_iter().collect()),
+            (clients, _) => clients,
+        };
+        self
+    }
+
+    /// Returns an iterator over the clients processed so far.
+    ///
+    /// Clients are in arbitrary order unless `ordered_clients` is set.
+    pub fn clients(&self) -> impl Iterator<Item = &Client<M>> + '_ {
+        self.clients.values()
+    }
+
+    /// Returns an iterator over the accounts processed so far, sorted by
+    /// Client ID.
+    pub fn accounts(&self) -> impl Iterator<Item = (u16, &Account<M>)> + '_ {
+        let mut accounts: Vec<(u16, &Account<M>)> = self
+            .clients
+            .values()
+            .map(|client| (client.id(), client.get()))
+            .collect();
+        if let Clients::Hashed(_) = self.clients {
+            accounts.sort_unstable_by_key(|(id, _)| *id);
+        }
+        accounts.into_iter()
+    }
+
+    /// Returns the client with Client ID `id` if it has been seen.
+    pub fn client(&self, id: u16) -> Option<&Client<M>> {
+        self.clients.get(id)
+    }
+
+    /// Returns `true` if a client with Client ID `id` has been seen.
+    pub fn contains_client(&self, id: u16) -> bool {
+        self.clients.get(id).is_some()
+    }
+
+    /// Consumes the `Engine` returning the clients keyed by Client ID.
+    pub fn into_clients(self) -> HashMap<u16, Client<M>> {
+        self.clients.into_map()
+    }
+
+    /// Returns the ledger of records processed so far in order.
+    ///
+    /// Empty unless enabled with `ledger_enabled`.
+    pub fn ledger(&self) -> &[LedgerEntry] {
+        self.ledger.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the balance before and after each applied transaction in order.
+    ///
+    /// Empty unless enabled with `balance_changes_enabled`.
+    pub fn balance_changes(&self) -> &[BalanceChange<M>] {
+        self.balance_changes.as_deref().unwrap_or_default()
+    }
+
+    /// Returns a warning for each record refused so far in order.
+    ///
+    /// Empty unless enabled with `warnings_enabled`.
+    pub fn warnings(&self) -> &[Warning] {
+        self.warnings.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the amount held by each open dispute keyed by the disputed
+    /// Transaction ID.
+    pub fn open_disputes(&self) -> &HashMap<u32, Decimal> {
+        &self.disputes
+    }
+
+    /// Returns the number of disputes applied to transaction `tx`, including
+    /// those since resolved.
+    pub fn dispute_count(&self, tx: u32) -> usize {
+        self.dispute_counts.get(&tx).copied().unwrap_or_default()
+    }
+
+    /// Writes the open disputes as CSV rows of `OpenDispute` sorted by
+    /// Transaction ID, to resume with `with_open_disputes`.
+    pub fn write_open_disputes<W: std::io::Write>(&self, writer: W) -> Result<(), Box<dyn Error>> {
+        // Each open dispute is held on the account of its client.
+        let clients: HashMap<u32, u16> = self
+            .clients
+            .values()
+            .flat_map(|client| {
+                let id = client.id();
+                client
+                    .get()
+                    .held_breakdown()
+                    .keys()
+                    .map(move |tx| (*tx, id))
+            })
+            .collect();
+        let mut disputes: Vec<(&u32, &Decimal)> = self.disputes.iter().collect();
+        disputes.sort_unstable();
+
+        let mut wtr = csv::Writer::from_writer(writer);
+        for (tx, amount) in disputes {
+            let client = *clients
+                .get(tx)
+                .ok_or_else(|| format!("Open dispute of transaction {tx} has no account"))?;
+            wtr.serialize(OpenDispute {
+                tx: *tx,
+                client,
+                amount: *amount,
+            })?;
+        }
+        // Without any disputes the header is written on its own.
+        if self.disputes.is_empty() {
+            wtr.write_record(["tx", "client", "amount"])?;
+        }
+        wtr.flush()?;
+
+        Ok(())
+    }
+
+    /// Returns the counters collected so far.
+    pub fn stats(&self) -> &RunStats {
+        &self.stats
+    }
+
+    /// Returns the number of records processed so far.
+    ///
+    /// As `RunStats::records`, for polling between calls to `process_chunk`.
+    pub fn records_seen(&self) -> usize {
+        self.stats.records
+    }
+
+    /// Returns the number of transactions applied so far.
+    ///
+    /// Coalesced deposits are counted once applied together.
+    pub fn records_applied(&self) -> usize {
+        self.stats.applied
+    }
+
+    /// Returns the number of transactions refused for any reason so far, see
+    /// `RunStats::rejected`.
+    pub fn records_skipped(&self) -> usize {
+        self.stats.rejected()
+    }
+
+    /// Writes all clients as CSV rows sorted by Client ID to `writer`.
+    ///
+    /// Rows are formatted according to the output options such as
+    /// `minor_units`. Inactive clients are left out under `only_active` and
+    /// locked clients with a zero balance under `LockedZeroBalance::Omit`.
+    /// Without any clients only the header row is written, unless
+    /// `omit_empty_header` is set.
+    ///
+    /// Rows are serialized directly to `writer`, the only allocation is the
+    /// list of client references to sort.
+    pub fn write<W: std::io::Write>(&self, writer: W) -> Result<(), Box<dyn Error>> {
+        let active = |client: &&Client<M>| {
+            !self.output.only_active
+                || self
+                    .tx_counts
+                    .get(&client.id())
+                    .is_some_and(|count| *count > 0)
+        };
+        let omitted = |client: &&Client<M>| {
+            self.output.locked_zero_balance == LockedZeroBalance::Omit
+                && client.is_locked()
+                && client.balance().is_zero()
+        };
+        let mut clients: Vec<&Client<M>> = self
+            .clients
+            .values()
+            .filter(|client| active(client) && !omitted(client))
+            .collect();
+        if let Clients::Hashed(_) = self.clients {
+            clients.sort_unstable();
+        }
+
+        let mut wtr = csv::Writer::from_writer(writer);
+        // Rows write the header before the first, without any it is written
+        // on its own.
+        if clients.is_empty() && !self.output.omit_empty_header {
+            wtr.write_record(self.output.headers())?;
+        }
+        for client in clients {
+            wtr.serialize(client.row(&self.output))?;
+        }
+        wtr.flush()?;
+
+        Ok(())
+    }
+
+    /// Processes all transaction records.
+    pub fn process<R: std::io::Read>(
+        &mut self,
+        mut transaction_records: csv::Reader<R>,
+    ) -> Result<(), Box<dyn Error>> {
+        let result = deserialize_records(&mut transaction_records, self.currency)?
+            .try_for_each(|result| self.process_record(result?));
+        self.finish();
+        result?;
+
+        let held = self.stats.total_held_remaining;
+        if self.fail_on_held_remaining && !held.is_zero() {
+            return Err(KoncordError::HeldRemaining { held }.into());
+        }
+
+        Ok(())
+    }
+
+    /// Processes at most `max` transaction records, returning how many were
+    /// processed.
+    ///
+    /// The reader is left after the last record processed so processing
+    /// resumes from there on the next call, until `0` is returned once the
+    /// records are exhausted. Blank lines are skipped without counting.
+    pub fn process_chunk<R: std::io::Read>(
+        &mut self,
+        transaction_records: &mut csv::Reader<R>,
+        max: usize,
+    ) -> Result<usize, KoncordError> {
+        let mut count = 0;
+        let result = deserialize_records(transaction_records, self.currency).and_then(|records| {
+            records.take(max).try_for_each(|result| {
+                count += 1;
+                self.process_record(result?)
+            })
+        });
+        self.finish();
+
+        result.map(|()| count).map_err(KoncordError::from)
+    }
+
+    /// Stages all transaction records without changing any balances.
+    ///
+    /// The records are processed against a copy of this engine, every
+    /// malformed record or failed transaction is collected rather than
+    /// stopping at the first. Only a batch without errors is staged, it is
+    /// applied with `commit`.
+    pub fn stage<R: std::io::Read>(
+        &self,
+        mut transaction_records: csv::Reader<R>,
+    ) -> Result<StagedBatch<M>, Vec<KoncordError>> {
+        let mut engine = self.clone();
+        let mut errors = Vec::new();
+
+        match deserialize_records(&mut transaction_records, engine.currency) {
+            Ok(records) => {
+                for result in records {
+                    if let Err(err) = result.and_then(|record| engine.process_record(record)) {
+                        errors.push(KoncordError::from(err));
+                    }
+                }
+            }
+            Err(err) => errors.push(KoncordError::from(err)),
+        }
+        engine.finish();
+
+        if errors.is_empty() {
+            Ok(StagedBatch {
+                engine,
+                staged_on: self.generation,
+            })
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Applies a batch staged by `stage`.
+    ///
+    /// The batch holds the whole state of the engine once its records are
+    /// processed. Fails with `KoncordError::StaleBatch`, leaving the engine as
+    /// is, if records were processed or rolled back since staging as they
+    /// would be discarded.
+    pub fn commit(&mut self, batch: StagedBatch<M>) -> Result<(), KoncordError> {
+        if batch.staged_on != self.generation {
+            return Err(KoncordError::StaleBatch);
+        }
+
+        *self = batch.engine;
+        Ok(())
+    }
+
+    /// Rolls back a batch of transaction records previously processed.
+    ///
+    /// The records are read in full then undone in reverse order with
+    /// `Account::undo`, returning the accounts to their balances before the
+    /// batch. Every record is assumed to have been applied, as when rolling
+    /// back a batch processed without rejections. Disputes release the funds
+    /// they still hold and resolves hold their amount again, chargebacks and
+    /// reversals cannot be undone and are counted as skipped.
+    pub fn rollback<R: std::io::Read>(
+        &mut self,
+        mut transaction_records: csv::Reader<R>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.apply_pending();
+        let records = deserialize_records(&mut transaction_records, self.currency)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for record in records.into_iter().rev() {
+            self.stats.records += 1;
+            self.undo_record(&record);
+        }
+        self.finish();
+
+        Ok(())
+    }
+
+    // Undo a single record of `rollback`.
+    fn undo_record(&mut self, record: &Record) {
+        self.generation += 1;
+        let (id, tx, kind) = (record.client_id(), record.tx(), record.kind());
+        let amount = match kind {
+            TransactionKind::Unknown(_) => {
+                self.stats.unknown_kinds += 1;
+                return;
+            }
+            TransactionKind::Dispute => self.disputes.remove(&tx),
+            TransactionKind::Resolve => record
+                .amount()
+                .or_else(|| self.deposits.get(&tx).map(|deposit| deposit.amount)),
+            _ => record.amount(),
+        };
+        let scale = self.scale();
+        let Some(amount) = amount.map(|amount| self.policy.rounding.round(amount, scale)) else {
+            self.stats.skipped += 1;
+            return;
+        };
+
+        let account = self.clients.entry(id, self.output.scale).get_mut();
+        let result = account.undo(kind, tx, amount);
+        if result == OpResult::Applied {
+            match kind {
+                TransactionKind::Deposit => {
+                    self.deposits.remove(&tx);
+                }
+                TransactionKind::Withdrawal => {
+                    self.withdrawals.remove(&tx);
+                }
+                TransactionKind::Dispute => {
+                    if let Some(count) = self.dispute_counts.get_mut(&tx) {
+                        *count -= 1;
+                        if *count == 0 {
+                            self.dispute_counts.remove(&tx);
+                        }
+                    }
+                }
+                TransactionKind::Resolve => {
+                    *self.disputes.entry(tx).or_default() += amount;
+                }
+                _ => {}
+            }
+        }
+        self.count(result);
+    }
+
+    /// Processes all transaction records from an async `stream`.
+    #[cfg(feature = "async")]
+    pub async fn process_stream<S>(&mut self, stream: S) -> Result<(), Box<dyn Error>>
+    where
+        S: futures::Stream<Item = Record>,
+    {
+        use futures::StreamExt;
+
+        let mut stream = std::pin::pin!(stream);
+        while let Some(record) = stream.next().await {
+            self.process_record(record)?;
+        }
+        self.finish();
+
+        Ok(())
+    }
+
+    /// Processes all transaction records, checkpointing the clients.
+    ///
+    /// After every `every` records all clients are written to `writer` as
+    /// with `write` so a crash loses at most `every` records. An `every` of
+    /// `0` never checkpoints.
+    pub fn process_with_checkpoint<R: std::io::Read, W: std::io::Write>(
+        &mut self,
+        mut transaction_records: csv::Reader<R>,
+        mut writer: W,
+        every: usize,
+    ) -> Result<(), Box<dyn Error>> {
+        let result =
+            deserialize_records(&mut transaction_records, self.currency)?.try_for_each(|result| {
+                self.process_record(result?)?;
+
+                if every > 0 && self.stats.records.is_multiple_of(every) {
+                    self.apply_pending();
+                    self.write(&mut writer)?;
+                }
+                Ok(())
+            });
+        self.finish();
+
+        result
+    }
+
+    // Process a deposit or withdrawal of `amount`.
+    //
+    // The bulk of records, applied directly to the account without the
+    // transaction state machine.
+    fn process_funds(
+        &mut self,
+        record: &Record,
+        amount: Decimal,
+        position: usize,
+        coalesce: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let (id, tx) = (record.client_id(), record.tx());
+        if amount < Decimal::ZERO {
+            return Err(KoncordError::NegativeAmount { tx, amount }.into());
+        }
+        let amount = self.policy.rounding.round(amount, self.scale());
+        let zero = amount == Decimal::ZERO;
+        if zero && !self.allow_zero_amount {
+            self.stats.zero_amounts += 1;
+            return Ok(());
+        }
+        let deposit = *record.kind() == TransactionKind::Deposit;
+        let reused = if deposit {
+            self.withdrawals.contains_key(&tx)
+        } else {
+            self.deposits.contains_key(&tx)
+        };
+        if reused {
+            self.stats.reused_tx += 1;
+        }
+        if deposit {
+            self.deposits.insert(
+                tx,
+                Deposit {
+                    client: id,
+                    amount,
+                    position,
+                },
+            );
+        }
+        if zero {
+            self.stats.applied += 1;
+            return Ok(());
+        }
+        if coalesce {
+            let net = self
+                .pending
+                .as_ref()
+                .map_or(Some(amount), |pending| pending.amount.checked_add(amount));
+            let account = self.clients.entry(id, self.output.scale).get();
+            if let Some(net) = net.filter(|net| account.deposit_fits(*net)) {
+                let pending = self.pending.get_or_insert(PendingDeposit {
+                    client: id,
+                    amount: Decimal::ZERO,
+                    count: 0,
+                });
+                pending.amount = net;
+                pending.count += 1;
+                return Ok(());
+            }
+            // A net deposit overflowing the balance would refuse deposits
+            // applied on their own, so the pending deposits are applied and
+            // this one separately.
+            self.apply_pending();
+        }
+
+        let scale = self.scale();
+        let account = self.clients.entry(id, self.output.scale).get_mut();
+        if !deposit
+            && self.reject_dust
+            && (account.available() - amount).normalize().scale() > scale
+        {
+            self.stats.dust_withdrawals += 1;
+            return Ok(());
+        }
+        let result = if deposit {
+            account.deposit(amount)
+        } else {
+            account.withdraw(amount)
+        };
+        if self.policy.saturating {
+            account.saturate();
+        }
+        // Refused withdrawals have nothing to reverse.
+        if !deposit && result == OpResult::Applied {
+            self.withdrawals.insert(
+                tx,
+                Deposit {
+                    client: id,
+                    amount,
+                    position,
+                },
+            );
+            if let Some(lookup) = &mut self.lookup {
+                lookup.applied(record.kind(), tx);
+            }
+        }
+        self.count(result);
+
+        Ok(())
+    }
+
+    // Processes all `records`, used by the shards of `run_parallel`.
+    pub(crate) fn process_iter<I>(&mut self, records: I) -> Result<(), Box<dyn Error>>
+    where
+        I: IntoIterator<Item = Record>,
+    {
+        let result = records
+            .into_iter()
+            .try_for_each(|record| self.process_record(record));
+        self.finish();
+
+        result
+    }
+
+    // Merge the state of a shard of disjoint clients into this engine.
+    pub(crate) fn merge(&mut self, other: Engine<M>) {
+        self.deposits.extend(other.deposits);
+        self.withdrawals.extend(other.withdrawals);
+        self.tx_counts.extend(other.tx_counts);
+        self.disputes.extend(other.disputes);
+        self.dispute_counts.extend(other.dispute_counts);
+        if let (Some(timestamps), Some(other)) = (&mut self.timestamps, other.timestamps) {
+            timestamps.extend(other);
+        }
+        if let (Some(ledger), Some(other)) = (&mut self.ledger, other.ledger) {
+            ledger.extend(other);
+        }
+        if let (Some(changes), Some(other)) = (&mut self.balance_changes, other.balance_changes) {
+            changes.extend(other);
+        }
+        if let (Some(warnings), Some(other)) = (&mut self.warnings, other.warnings) {
+            warnings.extend(other);
+        }
+        self.stats.merge(&other.stats);
+        self.clients.extend(other.clients.into_map());
+        self.finish();
+    }
+
+    // Complete processing, applying any pending deposits then reconciling
+    // every account.
+    fn finish(&mut self) {
+        self.apply_pending();
+
+        let mut violations: Vec<u16> = self
+            .clients
+            .values()
+            .filter(|client| {
+                let balance = client.balance();
+                balance.available().checked_add(balance.held()) != Some(balance.total())
+            })
+            .map(Client::id)
+            .collect();
+        violations.sort_unstable();
+        self.stats.invariant_violations = violations;
+        self.stats.total_held_remaining = self
+            .clients
+            .values()
+            .map(|client| client.balance().held())
+            .fold(Decimal::ZERO, Decimal::saturating_add);
+    }
+
+    // Apply the net of any coalesced deposits.
+    fn apply_pending(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            let result = self
+                .clients
+                .entry(pending.client, self.output.scale)
+                .get_mut()
+                .deposit_coalesced(pending.amount, pending.count);
+            for _ in 0..pending.count {
+                self.count(result);
+            }
+        }
+    }
+
+    // Count the outcome of an account operation.
+    fn count(&mut self, result: OpResult) {
+        self.last_result = Some(result);
+        self.stats.count(result);
+    }
+
+    // Process a single record.
+    fn process_record(&mut self, record: Record) -> Result<(), Box<dyn Error>> {
+        self.generation += 1;
+        // Only positive deposits coalesce, anything else may depend on the
+        // balance so the pending deposits are applied first.
+        let coalesce = self.coalesce_deposits
+            && *record.kind() == TransactionKind::Deposit
+            && record.amount().is_some_and(|amount| amount > Decimal::ZERO);
+        match &self.pending {
+            Some(pending) if coalesce && pending.client == record.client_id() => {}
+            _ => self.apply_pending(),
+        }
+        if self.balance_changes.is_none() && self.warnings.is_none() {
+            return self.apply_record(record, coalesce);
+        }
+
+        let (id, tx, kind) = (record.client_id(), record.tx(), record.kind().clone());
+        let before = match self.clients.get(id) {
+            Some(client) => client.balance().clone(),
+            None => Balance::with_scale(self.scale()),
+        };
+        let stats = self.warnings.is_some().then(|| self.stats.clone());
+        let applied = self.stats.applied;
+        self.last_result = None;
+        self.apply_record(record, coalesce)?;
+        if let (Some(warnings), Some(stats)) = (&mut self.warnings, stats) {
+            if let Some(reason) = self.stats.rejection_since(&stats, self.last_result) {
+                warnings.push(Warning {
+                    client: id,
+                    tx,
+                    reason,
+                });
+            }
+        }
+        if self.stats.applied > applied {
+            if let (Some(changes), Some(client)) = (&mut self.balance_changes, self.clients.get(id))
+            {
+                changes.push(BalanceChange {
+                    client: id,
+                    tx,
+                    kind,
+                    before,
+                    after: client.balance().clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    // Apply a single record, after any pending deposits.
+    fn apply_record(&mut self, record: Record, coalesce: bool) -> Result<(), Box<dyn Error>> {
+        let position = self.stats.records;
+        self.stats.records += 1;
+        if let Some(ledger) = &mut self.ledger {
+            ledger.push(LedgerEntry::from(&record));
+        }
+        if let (Some(timestamps), Some(timestamp)) = (&mut self.timestamps, record.timestamp()) {
+            let last = timestamps.entry(record.client_id()).or_insert(timestamp);
+            if timestamp < *last {
+                self.stats.out_of_order += 1;
+                return Ok(());
+            }
+            *last = timestamp;
+        }
+        if let TransactionKind::Unknown(_) = record.kind() {
+            self.stats.unknown_kinds += 1;
+            return Ok(());
+        }
+        if let (Some(max), Some(amount)) = (self.max_amount, record.amount()) {
+            if amount.abs() > max {
+                self.stats.over_max_amount += 1;
+                return Ok(());
+            }
+        }
+
+        let id = record.client_id();
+        if self.max_tx_per_client.is_some() || self.output.only_active {
+            let count = self.tx_counts.entry(id).or_default();
+            if self.max_tx_per_client.is_some_and(|max| *count >= max) {
+                self.stats.capped += 1;
+                return Ok(());
+            }
+            *count += 1;
+        }
+        if let (TransactionKind::Deposit | TransactionKind::Withdrawal, Some(amount)) =
+            (record.kind(), record.amount())
+        {
+            return self.process_funds(&record, amount, position, coalesce);
+        }
+
+        let client = self.clients.entry(id, self.output.scale);
+        let recieved = Transaction::<Received>::from(record);
+
+        if self.strict && recieved.amount().is_some() {
+            if let TransactionKind::Dispute
+            | TransactionKind::Resolve
+            | TransactionKind::Chargeback = recieved.kind()
+            {
+                return Err(KoncordError::UnexpectedAmount { tx: recieved.id() }.into());
+            }
+        }
+
+        let (kind, tx) = (recieved.kind().clone(), recieved.id());
+        match kind {
+            TransactionKind::Deposit
+            | TransactionKind::Withdrawal
+            | TransactionKind::Adjustment
+            | TransactionKind::Hold
+            | TransactionKind::Release
+            | TransactionKind::Fee => {
+                let zero = recieved.amount() == Some(Decimal::ZERO);
+                if zero && !self.allow_zero_amount {
+                    self.stats.zero_amounts += 1;
+                    return Ok(());
+                }
+                if zero {
+                    self.stats.applied += 1;
+                    return Ok(());
+                }
+            }
+            TransactionKind::Dispute => {
+                if let Some(deposit) = self
+                    .deposits
+                    .get(&tx)
+                    .filter(|deposit| deposit.client == id)
+                {
+                    if let Some(max_age) = self.max_dispute_age {
+                        if position - deposit.position > max_age {
+                            self.stats.disputes_expired += 1;
+                            return Ok(());
+                        }
+                    }
+                    if let Some(max) = self.max_open_disputes {
+                        if client.open_dispute_count() >= max {
+                            self.stats.disputes_over_limit += 1;
+                            return Ok(());
+                        }
+                    }
+                    if self.refuse_redispute && self.dispute_counts.contains_key(&tx) {
+                        self.stats.redisputes_refused += 1;
+                        return Ok(());
+                    }
+                }
+            }
+            TransactionKind::Chargeback
+                if self
+                    .disputes
+                    .get(&tx)
+                    .is_some_and(|amount| *amount > client.get().total()) =>
+            {
+                self.stats.excess_chargebacks += 1;
+            }
+            _ => {}
+        }
+
+        let mut indexes = Indexes {
+            deposits: &mut self.deposits,
+            withdrawals: &mut self.withdrawals,
+            disputes: &mut self.disputes,
+            dispute_counts: &mut self.dispute_counts,
+            lookup: self.lookup.as_mut(),
+        };
+        match dispatch(recieved, id, client.get_mut(), &self.policy, &mut indexes)? {
+            Outcome::Processed(result) => {
+                if kind == TransactionKind::Dispute && result == OpResult::SkippedInsufficientFunds
+                {
+                    self.stats.disputes_refused += 1;
+                }
+                self.count(result);
+            }
+            Outcome::Unreferenced => match kind {
+                TransactionKind::Chargeback => self.stats.orphan_chargebacks += 1,
+                TransactionKind::Correction => self.stats.orphan_corrections += 1,
+                _ => {}
+            },
+            Outcome::Disputed if kind == TransactionKind::Dispute => {
+                self.stats.redisputes_refused += 1;
+            }
+            Outcome::Disputed => self.stats.skipped += 1,
+        }
+
+        Ok(())
+    }
+}
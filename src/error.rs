@@ -0,0 +1,288 @@
+//! Errors raised while processing transaction records.
+
+use std::error::Error;
+
+use rust_decimal::Decimal;
+
+pub use crate::transaction::InvalidTransitionError;
+use crate::transaction::TransactionKind;
+
+/// Errors caused by malformed transaction records.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum KoncordError {
+    /// The record on `line` has an empty or absent Transaction ID column.
+    MissingTx { line: u64 },
+    /// The record on `line` exceeds `MAX_RECORD_SIZE`.
+    RecordTooLarge { line: u64 },
+    /// The deposit, withdrawal, adjustment, hold, release, fee or correction
+    /// on `line` has an empty or absent amount column.
+    MissingAmount { line: u64 },
+    /// The record on `line` has an amount `value` that is not a finite
+    /// decimal.
+    InvalidAmount { line: u64, value: String },
+    /// Transaction `tx` has a negative `amount`, amounts are positive
+    /// magnitudes.
+    NegativeAmount { tx: u32, amount: Decimal },
+    /// The dispute, resolve or chargeback of transaction `tx` has an amount
+    /// in strict mode.
+    UnexpectedAmount { tx: u32 },
+    /// The dispute of transaction `tx` is absent from the dispute lookup
+    /// records, which differ from the records processed.
+    LookupSourceMismatch { tx: u32 },
+    /// A `RecordSource` failed to read the next record.
+    Source { message: String },
+    /// An IO error occurred while `context`, such as `reading input`.
+    Io { context: String, message: String },
+    /// A CSV error occurred on `line`, if known, while `context`.
+    Csv {
+        context: String,
+        line: Option<u64>,
+        message: String,
+    },
+    /// The snapshot of `client` has a total that is not its available plus
+    /// held funds.
+    InvalidSnapshot { client: u16 },
+    /// Funds of `held` remain held across all accounts once processing
+    /// finished, under `Engine::fail_on_held_remaining`.
+    HeldRemaining { held: Decimal },
+    /// The sum of the balances of all clients exceeds `Decimal::MAX` when
+    /// summarized.
+    SummaryOverflow,
+    /// The batch committed was staged before records since processed by the
+    /// engine.
+    StaleBatch,
+    /// Both clients merged hold funds under Transaction ID `tx`.
+    MergeConflict { tx: u32 },
+    /// The funds of the clients merged overflow once added.
+    MergeOverflow,
+    /// A transaction attempted an invalid state transition.
+    InvalidTransition(InvalidTransitionError),
+}
+
+impl std::fmt::Display for KoncordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KoncordError::MissingTx { line } => {
+                write!(f, "Missing transaction ID on line {line}")
+            }
+            KoncordError::RecordTooLarge { line } => {
+                write!(f, "Record too large on line {line}")
+            }
+            KoncordError::MissingAmount { line } => {
+                write!(f, "Missing amount on line {line}")
+            }
+            KoncordError::InvalidAmount { line, value } => {
+                write!(f, "Invalid amount {value:?} on line {line}")
+            }
+            KoncordError::NegativeAmount { tx, amount } => {
+                write!(f, "Negative amount {amount} for transaction {tx}")
+            }
+            KoncordError::UnexpectedAmount { tx } => {
+                write!(f, "Unexpected amount for transaction {tx}")
+            }
+            KoncordError::LookupSourceMismatch { tx } => {
+                write!(
+                    f,
+                    "Dispute of transaction {tx} not found in the lookup records"
+                )
+            }
+            KoncordError::Source { message } => {
+                write!(f, "Failed to read record: {message}")
+            }
+            KoncordError::Io { context, message } => {
+                write!(f, "IO error {context}: {message}")
+            }
+            KoncordError::Csv {
+                context, message, ..
+            } => {
+                write!(f, "CSV error {context}: {message}")
+            }
+            KoncordError::InvalidSnapshot { client } => {
+                write!(
+                    f,
+                    "Invalid snapshot of client {client}, total is not available plus held"
+                )
+            }
+            KoncordError::HeldRemaining { held } => {
+                write!(f, "Funds of {held} remain held after processing")
+            }
+            KoncordError::SummaryOverflow => {
+                write!(f, "Sum of client balances overflowed when summarizing")
+            }
+            KoncordError::StaleBatch => {
+                write!(f, "Staged batch is stale, records were processed since")
+            }
+            KoncordError::MergeConflict { tx } => {
+                write!(f, "Both merged clients hold funds of transaction {tx}")
+            }
+            KoncordError::MergeOverflow => {
+                write!(f, "Funds of the merged clients overflow")
+            }
+            KoncordError::InvalidTransition(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for KoncordError {}
+
+// Context of IO and CSV errors converted with `From`.
+const READING_INPUT: &str = "reading input";
+
+impl KoncordError {
+    /// Replace the context of an IO or CSV error with the operation that
+    /// failed, such as `reading dispute lookup records`.
+    ///
+    /// Other errors are returned unchanged.
+    pub fn context(self, context: impl Into<String>) -> Self {
+        match self {
+            KoncordError::Io { message, .. } => KoncordError::Io {
+                context: context.into(),
+                message,
+            },
+            KoncordError::Csv { line, message, .. } => KoncordError::Csv {
+                context: context.into(),
+                line,
+                message,
+            },
+            err => err,
+        }
+    }
+}
+
+// Returns the error a `RecordLimit` failed a read with, if it did.
+fn limit_error(err: &std::io::Error) -> Option<KoncordError> {
+    err.get_ref()?.downcast_ref::<KoncordError>().cloned()
+}
+
+/// IO errors are converted with the context `reading input`, those of a
+/// `RecordLimit` to the `KoncordError` they hold.
+impl From<std::io::Error> for KoncordError {
+    fn from(err: std::io::Error) -> Self {
+        limit_error(&err).unwrap_or_else(|| KoncordError::Io {
+            context: READING_INPUT.to_string(),
+            message: err.to_string(),
+        })
+    }
+}
+
+/// CSV errors are converted with the context `reading input`, those caused by
+/// IO errors are converted as IO errors.
+impl From<csv::Error> for KoncordError {
+    fn from(err: csv::Error) -> Self {
+        match err.kind() {
+            csv::ErrorKind::Io(err) => limit_error(err).unwrap_or_else(|| KoncordError::Io {
+                context: READING_INPUT.to_string(),
+                message: err.to_string(),
+            }),
+            _ => KoncordError::Csv {
+                context: READING_INPUT.to_string(),
+                line: err.position().map(csv::Position::line),
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
+// Errors that are not already a `KoncordError` come from reading records.
+impl From<Box<dyn Error>> for KoncordError {
+    fn from(err: Box<dyn Error>) -> Self {
+        match err.downcast::<KoncordError>() {
+            Ok(err) => *err,
+            Err(err) => match err.downcast::<InvalidTransitionError>() {
+                Ok(err) => KoncordError::InvalidTransition(*err),
+                Err(err) => match err.downcast::<csv::Error>() {
+                    Ok(err) => KoncordError::from(*err),
+                    Err(err) => match err.downcast::<std::io::Error>() {
+                        Ok(err) => KoncordError::from(*err),
+                        Err(err) => KoncordError::Source {
+                            message: err.to_string(),
+                        },
+                    },
+                },
+            },
+        }
+    }
+}
+
+impl From<InvalidTransitionError> for KoncordError {
+    fn from(err: InvalidTransitionError) -> Self {
+        KoncordError::InvalidTransition(err)
+    }
+}
+
+/// Problems with a single record found by `Record::validate`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RecordError {
+    /// The record of `kind` requires an amount but has none.
+    MissingAmount { kind: TransactionKind },
+    /// The record of `kind` refers to another transaction so takes no
+    /// amount, but has `amount`.
+    UnexpectedAmount {
+        kind: TransactionKind,
+        amount: Decimal,
+    },
+    /// The record of `kind` has a negative `amount`, only adjustments are
+    /// signed.
+    NegativeAmount {
+        kind: TransactionKind,
+        amount: Decimal,
+    },
+    /// The `amount` has more decimal places than `scale`.
+    ExcessPrecision { amount: Decimal, scale: u32 },
+}
+
+impl std::fmt::Display for RecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordError::MissingAmount { kind } => {
+                write!(f, "Missing amount for {}", kind.as_str())
+            }
+            RecordError::UnexpectedAmount { kind, amount } => {
+                write!(f, "Unexpected amount {amount} for {}", kind.as_str())
+            }
+            RecordError::NegativeAmount { kind, amount } => {
+                write!(f, "Negative amount {amount} for {}", kind.as_str())
+            }
+            RecordError::ExcessPrecision { amount, scale } => {
+                write!(f, "Amount {amount} has more than {scale} decimal places")
+            }
+        }
+    }
+}
+
+impl Error for RecordError {}
+
+/// Records whose shape does not match their transaction type.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SchemaError {
+    /// The record on `line` could not be parsed.
+    Malformed { line: u64, message: String },
+    /// The record on `line` exceeds `MAX_RECORD_SIZE`.
+    RecordTooLarge { line: u64 },
+    /// The record of `kind` on `line` requires an amount but has none.
+    MissingAmount { line: u64, kind: TransactionKind },
+    /// The record of `kind` on `line` has a negative amount, only adjustments
+    /// are signed.
+    NegativeAmount { line: u64, kind: TransactionKind },
+}
+
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaError::Malformed { line, message } => {
+                write!(f, "Malformed record on line {line}: {message}")
+            }
+            SchemaError::RecordTooLarge { line } => {
+                write!(f, "Record too large on line {line}")
+            }
+            SchemaError::MissingAmount { line, kind } => {
+                write!(f, "Missing amount for {} on line {line}", kind.as_str())
+            }
+            SchemaError::NegativeAmount { line, kind } => {
+                write!(f, "Negative amount for {} on line {line}", kind.as_str())
+            }
+        }
+    }
+}
+
+impl Error for SchemaError {}
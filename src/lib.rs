@@ -1,17 +1,388 @@
 pub mod client;
+mod engine;
+mod error;
+mod limit;
+mod money;
+mod reconcile;
+mod source;
+mod summary;
+pub mod testing;
 mod transaction;
 
-use std::collections::HashMap;
+pub use crate::engine::{
+    BalanceChange, Engine, LedgerEntry, OpenDispute, RunStats, StagedBatch, Warning,
+};
+pub use crate::error::{InvalidTransitionError, KoncordError, RecordError, SchemaError};
+pub use crate::limit::RecordLimit;
+pub use crate::money::{MinorUnits, Money};
+pub use crate::reconcile::{reconcile, ClientDiff};
+pub use crate::source::RecordSource;
+pub use crate::summary::{report, summarize, Summary, TransactionReport};
+pub use crate::transaction::{Record, TransactionKind};
+
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::fs::File;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 use rust_decimal::Decimal;
 
-use crate::client::Client;
+use crate::client::{AccountPolicy, Client};
 use crate::transaction::{
-    ChargedBack, DisputeLookup, Processing, Received, Record, Resolved, Transaction,
-    TransactionKind,
+    dispatch, normalize_amount, parse_amount, Received, Referenced, Tracker, Transaction,
 };
 
+/// Returns the `csv::ReaderBuilder` used to read transaction records.
+///
+/// Whitespace is trimmed from all fields and records may omit the trailing
+/// amount column. Records may be terminated by `\n`, `\r\n` or `\r` and a
+/// leading UTF-8 BOM, as written by many Windows tools, is stripped before the
+/// headers are parsed so the `type` column still matches. Lines starting with
+/// `#` are comments and are ignored along with blank lines.
+pub fn reader_builder() -> csv::ReaderBuilder {
+    ReaderConfig::default().builder()
+}
+
+/// Configuration of the CSV reader for transaction records.
+///
+/// The default matches `reader_builder`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ReaderConfig {
+    /// Field delimiter, such as `b';'` or `b'\t'`.
+    pub delimiter: u8,
+    /// Trim whitespace from all fields.
+    pub trim: bool,
+    /// Allow records to omit trailing columns such as the amount.
+    pub flexible: bool,
+    /// The first record is the headers. Without headers columns are read in
+    /// the order `type`, `client`, `tx`, `amount`, `description`,
+    /// `timestamp`.
+    pub has_headers: bool,
+}
+
+impl Default for ReaderConfig {
+    fn default() -> Self {
+        ReaderConfig {
+            delimiter: b',',
+            trim: true,
+            flexible: true,
+            has_headers: true,
+        }
+    }
+}
+
+impl ReaderConfig {
+    /// Returns a `csv::ReaderBuilder` with this configuration.
+    ///
+    /// Records may be terminated by `\n`, `\r\n` or `\r` and lines starting
+    /// with `#` are comments regardless of configuration.
+    pub fn builder(&self) -> csv::ReaderBuilder {
+        let trim = if self.trim {
+            csv::Trim::All
+        } else {
+            csv::Trim::None
+        };
+
+        let mut builder = csv::ReaderBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .trim(trim)
+            .flexible(self.flexible)
+            .has_headers(self.has_headers)
+            .terminator(csv::Terminator::CRLF)
+            .comment(Some(b'#'));
+        builder
+    }
+
+    /// Returns a `csv::Reader` of the records in `rdr` with this
+    /// configuration, limited to `MAX_RECORD_SIZE` bytes per record by
+    /// `RecordLimit`.
+    pub fn from_reader<R: std::io::Read>(&self, rdr: R) -> csv::Reader<RecordLimit<R>> {
+        self.builder()
+            .from_reader(RecordLimit::new(rdr).delimiter(self.delimiter))
+    }
+
+    /// Returns a `csv::Reader` of the records in the file at `path` as
+    /// `from_reader`.
+    pub fn from_path<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> csv::Result<csv::Reader<RecordLimit<File>>> {
+        Ok(self.from_reader(File::open(path)?))
+    }
+}
+
+/// The number of digits to the right of the decimal point.
+///
+/// Balances start at four places past the decimal unless an `Engine` is set
+/// to another scale with `Engine::with_scale`.
+pub const DEFAULT_SCALE: u32 = 4;
+
+/// Maximum size in bytes of a single record.
+///
+/// `csv::ReaderBuilder` has no size limit, readers built by
+/// `ReaderConfig::from_reader` fail with `KoncordError::RecordTooLarge` as a
+/// record passes the limit, see `RecordLimit`. Records of other readers are
+/// only rejected once read whole, when their fields exceed the limit.
+pub const MAX_RECORD_SIZE: usize = 4096;
+
+// Column order of records without headers.
+const DEFAULT_HEADERS: [&str; 6] = ["type", "client", "tx", "amount", "description", "timestamp"];
+
+// Returns the headers of `transaction_records` or `DEFAULT_HEADERS` if it has
+// none.
+pub(crate) fn record_headers<R: std::io::Read>(
+    transaction_records: &mut csv::Reader<R>,
+) -> csv::Result<csv::StringRecord> {
+    if transaction_records.has_headers() {
+        Ok(transaction_records.headers()?.clone())
+    } else {
+        Ok(default_headers())
+    }
+}
+
+pub(crate) fn default_headers() -> csv::StringRecord {
+    csv::StringRecord::from(DEFAULT_HEADERS.to_vec())
+}
+
+/// Processes all transaction records and writes the resulting clients.
+///
+/// Processing is done by an [`Engine`], clients are then written to `writer`
+/// as CSV rows sorted by Client ID.
+pub fn run_and_write<R: std::io::Read, W: std::io::Write>(
+    transaction_records: csv::Reader<R>,
+    writer: W,
+) -> Result<(), Box<dyn Error>> {
+    let mut engine = Engine::new();
+    engine.process(transaction_records)?;
+    engine.write(writer)
+}
+
+/// Processes all transaction records, checkpointing the clients to `writer`.
+///
+/// Processing is done by an [`Engine`] which writes all clients to `writer`
+/// after every `every` records. Returns the clients keyed by Client ID.
+pub fn run_with_checkpoint<R: std::io::Read, W: std::io::Write>(
+    transaction_records: csv::Reader<R>,
+    writer: W,
+    every: usize,
+) -> Result<HashMap<u16, Client>, Box<dyn Error>> {
+    let mut engine = Engine::new();
+    engine.process_with_checkpoint(transaction_records, writer, every)?;
+    Ok(engine.into_clients())
+}
+
+/// Validates all transaction records without processing them.
+///
+/// Every record is parsed and checked as when processing, returning all issues
+/// found rather than stopping at the first. Records that cannot be
+/// deserialized are reported as `KoncordError::Csv`, validation stops at the
+/// first that cannot be read such as one exceeding `MAX_RECORD_SIZE`.
+pub fn run_validate<R: std::io::Read>(
+    mut transaction_records: csv::Reader<R>,
+) -> Vec<KoncordError> {
+    let records = match deserialize_records(&mut transaction_records, None) {
+        Ok(records) => records,
+        Err(err) => return vec![KoncordError::from(err)],
+    };
+
+    let mut issues = vec![];
+    for result in records {
+        match result {
+            // Adjustments and corrections are the only signed amounts.
+            Ok(record) => match (record.kind(), record.amount()) {
+                (kind, _) if kind.is_signed() => {}
+                (_, Some(amount)) if amount < Decimal::ZERO => {
+                    let tx = record.tx();
+                    issues.push(KoncordError::NegativeAmount { tx, amount });
+                }
+                _ => {}
+            },
+            Err(err) => {
+                let read_failed = err
+                    .downcast_ref::<csv::Error>()
+                    .is_some_and(|err| matches!(err.kind(), csv::ErrorKind::Io(_)));
+                issues.push(KoncordError::from(err));
+                // Reading further records would fail as well.
+                if read_failed {
+                    break;
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+/// Checks that every transaction record has the shape its type requires.
+///
+/// Deposits, withdrawals, adjustments, holds, releases and corrections
+/// require an amount, which is non-negative for all but adjustments and
+/// corrections. Disputes, resolves, chargebacks and reversals only require a
+/// Transaction ID. No balances are applied, all records are checked and every
+/// `SchemaError` returned. Records of unknown
+/// types are ignored as when processing. Checking stops at the first record
+/// that cannot be read, such as one exceeding `MAX_RECORD_SIZE`.
+pub fn validate_schema<R: std::io::Read>(
+    mut transaction_records: csv::Reader<R>,
+) -> Result<(), Vec<SchemaError>> {
+    let malformed = |line: u64, err: &dyn Error| SchemaError::Malformed {
+        line,
+        message: err.to_string(),
+    };
+
+    let headers =
+        record_headers(&mut transaction_records).map_err(|err| vec![malformed(1, &err)])?;
+
+    let mut errors = vec![];
+    for result in transaction_records.records() {
+        let raw = match result {
+            Ok(raw) => raw,
+            Err(err) => match err.kind() {
+                csv::ErrorKind::Io(_) => {
+                    errors.push(match KoncordError::from(err) {
+                        KoncordError::RecordTooLarge { line } => {
+                            SchemaError::RecordTooLarge { line }
+                        }
+                        err => malformed(0, &err),
+                    });
+                    break;
+                }
+                _ => {
+                    let line = err.position().map_or(0, |position| position.line());
+                    errors.push(malformed(line, &err));
+                    continue;
+                }
+            },
+        };
+        // Lines of only whitespace are blank once trimmed.
+        if raw.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+
+        let line = raw.position().map_or(0, |position| position.line());
+        if raw.as_slice().len() > MAX_RECORD_SIZE {
+            errors.push(SchemaError::RecordTooLarge { line });
+            continue;
+        }
+        let record: Record = match raw.deserialize(Some(&headers)) {
+            Ok(record) => record,
+            Err(err) => {
+                errors.push(malformed(line, &err));
+                continue;
+            }
+        };
+
+        let kind = record.kind().clone();
+        match (&kind, record.amount()) {
+            (
+                TransactionKind::Unknown(_)
+                | TransactionKind::Dispute
+                | TransactionKind::Resolve
+                | TransactionKind::Chargeback
+                | TransactionKind::Reversal,
+                _,
+            ) => {}
+            (_, None) => errors.push(SchemaError::MissingAmount { line, kind }),
+            (kind, Some(_)) if kind.is_signed() => {}
+            (_, Some(amount)) if amount < Decimal::ZERO => {
+                errors.push(SchemaError::NegativeAmount { line, kind });
+            }
+            _ => {}
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Processes all transaction records across `threads` threads.
+///
+/// Records are sharded by Client ID so each client, and the deposits its
+/// disputes look up, are processed in order by a single `Engine` built with
+/// `engine`. The shards are merged into the returned `Engine`, its ledger
+/// holds the records of each shard in turn and `max_dispute_age` counts the
+/// records of a shard. A `threads` of `0` or `1` processes sequentially.
+pub fn run_parallel<R, F>(
+    mut transaction_records: csv::Reader<R>,
+    threads: usize,
+    engine: F,
+) -> Result<Engine, Box<dyn Error>>
+where
+    R: std::io::Read,
+    F: Fn() -> Engine + Sync,
+{
+    if threads <= 1 {
+        let mut engine = engine();
+        engine.process(transaction_records)?;
+        return Ok(engine);
+    }
+
+    std::thread::scope(|scope| {
+        let (senders, shards): (Vec<_>, Vec<_>) = (0..threads)
+            .map(|_| {
+                let (sender, receiver) = std::sync::mpsc::sync_channel::<Record>(1024);
+                let engine = &engine;
+                let shard = scope.spawn(move || {
+                    let mut engine = engine();
+                    // Errors are not `Send`, keep their message.
+                    engine
+                        .process_iter(receiver)
+                        .map_err(|err| err.to_string())?;
+                    Ok::<_, String>(engine)
+                });
+                (sender, shard)
+            })
+            .unzip();
+
+        let mut merged = engine();
+        let mut result = Ok(());
+        for record in deserialize_records(&mut transaction_records, merged.currency())? {
+            match record {
+                Ok(record) => {
+                    let shard = usize::from(record.client_id()) % threads;
+                    // The shard failed, its error is returned when joined.
+                    if senders[shard].send(record).is_err() {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+        drop(senders);
+
+        for shard in shards {
+            let shard = shard.join().map_err(|_| "shard thread panicked")??;
+            merged.merge(shard);
+        }
+        result?;
+
+        Ok(merged)
+    })
+}
+
+/// Processes all transaction records from an async `stream`.
+///
+/// Processing is done by an [`Engine`] as each record arrives, disputes are
+/// looked up in its in-memory index as there is no file to search. Returns the
+/// clients keyed by Client ID.
+#[cfg(feature = "async")]
+pub async fn run_async<S>(stream: S) -> Result<HashMap<u16, Client>, Box<dyn Error>>
+where
+    S: futures::Stream<Item = Record>,
+{
+    let mut engine = Engine::new();
+    engine.process_stream(stream).await?;
+    Ok(engine.into_clients())
+}
+
 /// Processes all transaction records.
 ///
 /// Each record is processed sequentially through the states shown below. The
@@ -40,70 +411,391 @@ use crate::transaction::{
 /// │Processing◄─────┴──────────┴──────────┘
 /// └────┬─────┘
 ///      │
-///      │
-/// ┌────┴───┐
-/// │Complete│
-/// └────────┘
+///      ├─────────┐
+/// ┌────┴───┐ ┌───┴──┐
+/// │Complete│ │Failed│
+/// └────────┘ └──────┘
 /// ```
-pub fn run<R: std::io::Read + std::io::Seek>(
+///
+/// Records are read from any [`RecordSource`] such as a `csv::Reader`, dispute
+/// lookups read the transaction records at `records_path`. Processing fails
+/// with `KoncordError::LookupSourceMismatch` if a dispute is not found there,
+/// as the records at `records_path` differ from those processed.
+pub fn run<S: RecordSource>(
+    clients: &mut HashMap<u16, Client>,
+    transaction_records: S,
+    records_path: &str,
+) -> Result<(), Box<dyn Error>> {
+    run_with_client_filter(clients, transaction_records, records_path, Some)
+}
+
+/// Processes all transaction records at `records_path` read with `config`.
+///
+/// As `run` for files that are not read by `reader_builder`, such as
+/// semicolon delimited files. Dispute lookups use the same configuration.
+pub fn run_with_config(
     clients: &mut HashMap<u16, Client>,
-    mut transaction_records: csv::Reader<R>,
     records_path: &str,
+    config: &ReaderConfig,
 ) -> Result<(), Box<dyn Error>> {
-    let mut disputes: HashMap<u32, Decimal> = HashMap::new();
+    let transaction_records = config.from_path(records_path)?;
+    let hooks = Hooks::default();
+    process_records(
+        clients,
+        transaction_records,
+        records_path,
+        config,
+        Some,
+        hooks,
+    )
+}
 
-    for result in transaction_records.deserialize() {
-        let record: Record = result?;
-        let client: &mut Client = clients
-            .entry(record.client_id())
-            .or_insert(Client::new(record.client_id()));
+/// Processes all transaction records as `run`, writing diagnostics.
+///
+/// Every record is written to `diagnostics` on its own line as it is
+/// processed, prefixed by `record`, or by `skipped` for records of unknown
+/// types that are not processed.
+pub fn run_with_diagnostics<S: RecordSource>(
+    clients: &mut HashMap<u16, Client>,
+    transaction_records: S,
+    records_path: &str,
+    diagnostics: &mut dyn std::io::Write,
+) -> Result<(), Box<dyn Error>> {
+    let config = ReaderConfig::default();
+    let hooks = Hooks {
+        diagnostics: Some(diagnostics),
+        ..Hooks::default()
+    };
+    process_records(
+        clients,
+        transaction_records,
+        records_path,
+        &config,
+        Some,
+        hooks,
+    )
+}
 
-        process_record(record, client, &mut disputes, records_path)?;
-    }
+/// Processes all transaction records as `run`, reporting progress.
+///
+/// `progress` is called after every `every` records read with the number of
+/// records read so far and the time elapsed since processing started, so
+/// throughput of large runs can be monitored. An `every` of `0` never reports
+/// progress.
+pub fn run_with_progress<S, P>(
+    clients: &mut HashMap<u16, Client>,
+    transaction_records: S,
+    records_path: &str,
+    every: usize,
+    mut progress: P,
+) -> Result<(), Box<dyn Error>>
+where
+    S: RecordSource,
+    P: FnMut(usize, Duration),
+{
+    let config = ReaderConfig::default();
+    let hooks = Hooks {
+        progress: Some(&mut progress),
+        progress_every: every,
+        ..Hooks::default()
+    };
+    process_records(
+        clients,
+        transaction_records,
+        records_path,
+        &config,
+        Some,
+        hooks,
+    )
+}
 
-    Ok(())
+/// Processes all transaction records, remapping or dropping Client IDs.
+///
+/// `filter` is called with the Client ID of every record before it is
+/// processed. Returning `None` skips the record entirely while returning
+/// `Some(id)` processes the record against the account of Client ID `id`.
+///
+/// Dispute lookups still match against the Client ID as it appears in the
+/// records so remapped clients can dispute their own transactions.
+pub fn run_with_client_filter<S, F>(
+    clients: &mut HashMap<u16, Client>,
+    transaction_records: S,
+    records_path: &str,
+    filter: F,
+) -> Result<(), Box<dyn Error>>
+where
+    S: RecordSource,
+    F: Fn(u16) -> Option<u16>,
+{
+    let config = ReaderConfig::default();
+    let hooks = Hooks::default();
+    process_records(
+        clients,
+        transaction_records,
+        records_path,
+        &config,
+        filter,
+        hooks,
+    )
 }
 
-// Process a single record.
-fn process_record(
-    record: Record,
-    client: &mut Client,
-    disputes: &mut HashMap<u32, Decimal>,
+// Observers of the records read by `process_records`.
+#[derive(Default)]
+struct Hooks<'a> {
+    // Records are written as described by `run_with_diagnostics`.
+    diagnostics: Option<&'a mut dyn std::io::Write>,
+    // Called every `progress_every` records as described by
+    // `run_with_progress`.
+    progress: Option<&'a mut dyn FnMut(usize, Duration)>,
+    progress_every: usize,
+}
+
+// Processes all transaction records as `run_with_client_filter`, dispute
+// lookups read `records_path` with `config`. Records read are observed by
+// `hooks`.
+fn process_records<S, F>(
+    clients: &mut HashMap<u16, Client>,
+    mut transaction_records: S,
     records_path: &str,
-) -> Result<(), Box<dyn Error>> {
-    let recieved = Transaction::<Received>::from(record);
+    config: &ReaderConfig,
+    filter: F,
+    mut hooks: Hooks,
+) -> Result<(), Box<dyn Error>>
+where
+    S: RecordSource,
+    F: Fn(u16) -> Option<u16>,
+{
+    let mut tracked = Tracked::new(records_path, config);
+    let start = Instant::now();
+    let mut count: usize = 0;
 
-    match recieved.kind() {
-        TransactionKind::Deposit | TransactionKind::Withdrawal => {
-            let processing = Transaction::<Processing>::try_from(recieved)?;
-            processing.process(client.get_mut());
-        }
-        TransactionKind::Dispute => {
-            let mut dispute_lookup = Transaction::<DisputeLookup>::try_from(recieved)?;
-            if let Some(record) = lookup_record(records_path, dispute_lookup.tx(), client.id())? {
-                disputes.insert(record.tx(), record.amount().unwrap());
-                dispute_lookup.set_amount(record.amount());
-                let processing = Transaction::<Processing>::try_from(dispute_lookup)?;
-                processing.process(client.get_mut());
+    while let Some(record) = transaction_records.next_record()? {
+        count += 1;
+        if let Some(progress) = &mut hooks.progress {
+            if hooks.progress_every > 0 && count.is_multiple_of(hooks.progress_every) {
+                progress(count, start.elapsed());
             }
         }
-        TransactionKind::Resolve => {
-            let mut resolved = Transaction::<Resolved>::try_from(recieved)?;
-            if let Some(amount) = disputes.remove(&resolved.tx()) {
-                resolved.set_amount(Some(amount));
-                let processing = Transaction::<Processing>::try_from(resolved)?;
-                processing.process(client.get_mut());
-            }
+        // Records of unknown types are skipped.
+        let skipped = matches!(record.kind(), TransactionKind::Unknown(_));
+        if let Some(diagnostics) = &mut hooks.diagnostics {
+            let prefix = if skipped { "skipped" } else { "record" };
+            writeln!(diagnostics, "{prefix} {record:?}")?;
+        }
+        if skipped {
+            continue;
+        }
+        let id = match filter(record.client_id()) {
+            Some(id) => id,
+            None => continue,
+        };
+        let client: &mut Client = clients.entry(id).or_insert(Client::new(id));
+
+        process_record(record, client, &mut tracked)?;
+    }
+
+    Ok(())
+}
+
+// Deserialize each transaction record.
+//
+// Records with an empty or absent Transaction ID, an amount that is not a
+// finite decimal or deposits and withdrawals without an amount are reported as
+// a `KoncordError` rather than an opaque deserialization or state transition
+// error. Amounts may have a leading `+` and `currency` symbol.
+pub(crate) fn deserialize_records<R: std::io::Read>(
+    transaction_records: &mut csv::Reader<R>,
+    currency: Option<char>,
+) -> Result<impl Iterator<Item = Result<Record, Box<dyn Error>>> + '_, Box<dyn Error>> {
+    let headers = record_headers(transaction_records)?;
+
+    let records = transaction_records.records().filter(|result| match result {
+        // Lines of only whitespace are blank once trimmed.
+        Ok(raw) => raw.iter().any(|field| !field.is_empty()),
+        Err(_) => true,
+    });
+
+    Ok(records.map(move |result| deserialize_record(&result?, &headers, currency)))
+}
+
+// Deserialize a single non-blank transaction record, see
+// `deserialize_records`.
+pub(crate) fn deserialize_record(
+    raw: &csv::StringRecord,
+    headers: &csv::StringRecord,
+    currency: Option<char>,
+) -> Result<Record, Box<dyn Error>> {
+    let tx_column = headers.iter().position(|header| header == "tx");
+    let amount_column = headers.iter().position(|header| header == "amount");
+    let line = raw.position().map_or(0, |position| position.line());
+    if raw.as_slice().len() > MAX_RECORD_SIZE {
+        return Err(KoncordError::RecordTooLarge { line }.into());
+    }
+    let field = |column: Option<usize>| column.and_then(|column| raw.get(column));
+
+    if field(tx_column).unwrap_or("").is_empty() {
+        return Err(KoncordError::MissingTx { line }.into());
+    }
+    // The record is only copied when normalizing changes the amount.
+    let mut normalized = None;
+    if let (Some(column), Some(value)) = (amount_column, field(amount_column)) {
+        let amount = normalize_amount(value, currency);
+        if !amount.is_empty() && parse_amount(&amount).is_none() {
+            let value = value.to_string();
+            return Err(KoncordError::InvalidAmount { line, value }.into());
         }
-        TransactionKind::Chargeback => {
-            let mut chargeback = Transaction::<ChargedBack>::try_from(recieved)?;
-            if let Some(amount) = disputes.remove(&chargeback.tx()) {
-                chargeback.set_amount(Some(amount));
-                let processing = Transaction::<Processing>::try_from(chargeback)?;
-                processing.process(client.get_mut());
+        if amount != value {
+            let mut fields: csv::StringRecord = raw
+                .iter()
+                .enumerate()
+                .map(|(index, field)| if index == column { &amount } else { field })
+                .collect();
+            fields.set_position(raw.position().cloned());
+            normalized = Some(fields);
+        }
+    }
+
+    let record: Record = normalized
+        .as_ref()
+        .unwrap_or(raw)
+        .deserialize(Some(headers))?;
+    if record.amount().is_none()
+        && matches!(
+            record.kind(),
+            TransactionKind::Deposit
+                | TransactionKind::Withdrawal
+                | TransactionKind::Adjustment
+                | TransactionKind::Hold
+                | TransactionKind::Release
+                | TransactionKind::Fee
+                | TransactionKind::Correction
+        )
+    {
+        return Err(KoncordError::MissingAmount { line }.into());
+    }
+
+    Ok(record)
+}
+
+// Transactions referenced by records, found by reading the records at
+// `records_path` with `config` as `run` does.
+#[derive(Debug, Clone)]
+pub(crate) struct Lookup {
+    records_path: String,
+    config: ReaderConfig,
+    // Withdrawals applied, only these may be reversed.
+    withdrawals: HashSet<u32>,
+    // Deposits and withdrawals reversed, which may be neither disputed nor
+    // reversed again.
+    reversed: HashSet<u32>,
+}
+
+impl Lookup {
+    pub(crate) fn new(records_path: &str, config: &ReaderConfig) -> Self {
+        Lookup {
+            records_path: records_path.to_string(),
+            config: config.clone(),
+            withdrawals: HashSet::new(),
+            reversed: HashSet::new(),
+        }
+    }
+
+    // Returns the transaction `tx` of client `id` as referenced by a record of
+    // `kind`, see `Tracker::referenced`.
+    pub(crate) fn referenced(
+        &self,
+        kind: &TransactionKind,
+        tx: u32,
+        id: u16,
+    ) -> Result<Option<Referenced>, Box<dyn Error>> {
+        if *kind != TransactionKind::Correction && self.reversed.contains(&tx) {
+            return Ok(None);
+        }
+        let Some(record) = lookup_record(&self.records_path, &self.config, tx, id)? else {
+            return Ok(None);
+        };
+        let referenced = match (kind, record.kind()) {
+            // The dispute itself has Transaction ID `tx` so is found when the
+            // disputed transaction is absent, holding its own amount.
+            (TransactionKind::Dispute, _) | (_, TransactionKind::Deposit) => true,
+            (TransactionKind::Correction, TransactionKind::Withdrawal) => true,
+            (TransactionKind::Reversal, TransactionKind::Withdrawal) => {
+                self.withdrawals.contains(&tx)
+            }
+            _ => false,
+        };
+
+        Ok(referenced.then(|| Referenced {
+            kind: record.kind().clone(),
+            amount: record.amount(),
+        }))
+    }
+
+    // Notes that transaction `tx` of `kind` was applied to its account.
+    pub(crate) fn applied(&mut self, kind: &TransactionKind, tx: u32) {
+        match kind {
+            TransactionKind::Withdrawal => {
+                self.withdrawals.insert(tx);
+            }
+            TransactionKind::Reversal => {
+                self.reversed.insert(tx);
             }
+            _ => {}
+        }
+    }
+}
+
+// Transactions tracked while processing records with `process_record`.
+struct Tracked {
+    lookup: Lookup,
+    // Funds held by open disputes.
+    disputes: HashMap<u32, Decimal>,
+}
+
+impl Tracked {
+    fn new(records_path: &str, config: &ReaderConfig) -> Self {
+        Tracked {
+            lookup: Lookup::new(records_path, config),
+            disputes: HashMap::new(),
         }
     }
+}
+
+impl Tracker for Tracked {
+    fn referenced(
+        &mut self,
+        kind: &TransactionKind,
+        tx: u32,
+        id: u16,
+    ) -> Result<Option<Referenced>, Box<dyn Error>> {
+        self.lookup.referenced(kind, tx, id)
+    }
+
+    fn disputes(&mut self) -> &mut HashMap<u32, Decimal> {
+        &mut self.disputes
+    }
+
+    fn applied(&mut self, kind: &TransactionKind, tx: u32) {
+        self.lookup.applied(kind, tx);
+    }
+}
+
+// Process a single record.
+fn process_record(
+    record: Record,
+    client: &mut Client,
+    tracked: &mut Tracked,
+) -> Result<(), Box<dyn Error>> {
+    let id = record.client_id();
+    let recieved = Transaction::<Received>::from(record);
+    // Refused transactions are not reported by `run`.
+    dispatch(
+        recieved,
+        id,
+        client.get_mut(),
+        &AccountPolicy::default(),
+        tracked,
+    )?;
 
     Ok(())
 }
@@ -111,23 +803,27 @@ fn process_record(
 // Return record matching Transaction ID `tx` if found, else None.
 //
 // Checks client IDs match to prevent clients from submitting disputes against
-// accounts that are not theirs.
-fn lookup_record(records_path: &str, tx: u32, id: u16) -> Result<Option<Record>, Box<dyn Error>> {
-    let mut search_records = csv::ReaderBuilder::new()
-        .trim(csv::Trim::All)
-        .flexible(true)
-        .from_path(records_path)?;
-
-    let mut result: Option<Record> = None;
-    for record_result in search_records.deserialize() {
-        let record: Record = record_result?;
+// accounts that are not theirs. The dispute itself has Transaction ID `tx` so
+// is always found when `records_path` holds the records being processed,
+// finding nothing means the lookup source differs.
+fn lookup_record(
+    records_path: &str,
+    config: &ReaderConfig,
+    tx: u32,
+    id: u16,
+) -> Result<Option<Record>, Box<dyn Error>> {
+    let lookup =
+        |err: Box<dyn Error>| KoncordError::from(err).context("reading dispute lookup records");
+    let mut search_records = config
+        .from_path(records_path)
+        .map_err(|err| lookup(err.into()))?;
+
+    for record_result in deserialize_records(&mut search_records, None).map_err(lookup)? {
+        let record = record_result.map_err(lookup)?;
         if record.tx() == tx {
-            if record.client_id() == id {
-                result = Some(record);
-            }
-            break;
+            return Ok((record.client_id() == id).then_some(record));
         }
     }
 
-    Ok(result)
+    Err(KoncordError::LookupSourceMismatch { tx }.into())
 }
@@ -0,0 +1,145 @@
+//! Bounding the size of records before they are read into memory.
+//!
+//! `csv::Reader` reads a whole record into memory before it can be checked,
+//! `RecordLimit` scans the bytes beneath it so an oversized record fails as
+//! soon as it passes `MAX_RECORD_SIZE`.
+
+use std::io::{self, Read};
+
+use crate::error::KoncordError;
+use crate::MAX_RECORD_SIZE;
+
+/// Reads records from `R`, failing once a record exceeds `MAX_RECORD_SIZE`
+/// bytes.
+///
+/// Every byte of a record counts towards the limit including delimiters,
+/// quotes and padding, comment lines do not count. Reads of an oversized
+/// record fail with an IO error holding `KoncordError::RecordTooLarge`, as do
+/// all reads after it. Records are split as by `ReaderConfig::builder` with
+/// the delimiter set by `delimiter`.
+#[derive(Debug)]
+pub struct RecordLimit<R> {
+    inner: R,
+    delimiter: u8,
+    state: State,
+    // Bytes of the current record so far.
+    size: usize,
+    line: u64,
+    // Line the current record started on.
+    record_line: u64,
+    // Line of the record that exceeded the limit.
+    exceeded: Option<u64>,
+}
+
+// Position within a record, enough to find the record terminators.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum State {
+    RecordStart,
+    FieldStart,
+    Field,
+    Quoted,
+    // A quote in a quoted field, either closing it or escaping a quote.
+    QuoteEnd,
+    Comment,
+}
+
+impl<R: Read> RecordLimit<R> {
+    /// Limit the records read from `inner`, delimited by `,`.
+    pub fn new(inner: R) -> Self {
+        RecordLimit {
+            inner,
+            delimiter: b',',
+            state: State::RecordStart,
+            size: 0,
+            line: 1,
+            record_line: 1,
+            exceeded: None,
+        }
+    }
+
+    /// Split fields with `delimiter`, such as `b';'` or `b'\t'`.
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    // Advance past `byte`, counting it towards the current record.
+    fn scan(&mut self, byte: u8) {
+        if byte == b'\n' {
+            self.line += 1;
+        }
+        self.state = match (self.state, byte) {
+            (State::Quoted, b'"') => State::QuoteEnd,
+            (State::Quoted, _) | (State::QuoteEnd, b'"') => State::Quoted,
+            (_, b'\n' | b'\r') => State::RecordStart,
+            (State::Comment, _) | (State::RecordStart, b'#') => State::Comment,
+            (State::RecordStart | State::FieldStart, b'"') => State::Quoted,
+            (_, byte) if byte == self.delimiter => State::FieldStart,
+            _ => State::Field,
+        };
+        match self.state {
+            State::RecordStart => {
+                self.size = 0;
+                self.record_line = self.line;
+            }
+            State::Comment => {}
+            _ => self.size += 1,
+        }
+    }
+}
+
+impl<R: Read> Read for RecordLimit<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some(line) = self.exceeded {
+            return Err(too_large(line));
+        }
+
+        let read = self.inner.read(buf)?;
+        for &byte in &buf[..read] {
+            self.scan(byte);
+            if self.size > MAX_RECORD_SIZE {
+                self.exceeded = Some(self.record_line);
+                return Err(too_large(self.record_line));
+            }
+        }
+
+        Ok(read)
+    }
+}
+
+fn too_large(line: u64) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        KoncordError::RecordTooLarge { line },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Returns the line of the oversized record reading `input` fails on.
+    fn exceeded(input: &[u8]) -> Option<u64> {
+        let mut limit = RecordLimit::new(input);
+        let err = io::copy(&mut limit, &mut io::sink()).err()?;
+        match err.get_ref()?.downcast_ref::<KoncordError>()? {
+            KoncordError::RecordTooLarge { line } => Some(*line),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn record_limit() {
+        let field = "9".repeat(MAX_RECORD_SIZE);
+        assert_eq!(exceeded(format!("a\n{}\n", &field[1..]).as_bytes()), None);
+        assert_eq!(exceeded(format!("a\r\n{field},\n").as_bytes()), Some(2));
+        // Terminators in quoted fields do not end the record.
+        let quoted = field.replace("99", "9\n");
+        assert_eq!(
+            exceeded(format!("a\n\"{quoted}\"\"\"\n").as_bytes()),
+            Some(2)
+        );
+        // Comments do not count.
+        assert_eq!(exceeded(format!("#{field}\na\n").as_bytes()), None);
+    }
+}
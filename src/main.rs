@@ -1,26 +1,242 @@
 use std::collections::HashMap;
 use std::error::Error;
+use std::fs::File;
+use std::path::{Path, PathBuf};
 
-use koncord::client::Client;
-use koncord::run;
+use koncord::client::{load_clients, Client, ClientSnapshot, OutputOptions};
+use koncord::{run_parallel, run_validate, summarize, Engine, ReaderConfig, RecordLimit};
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<String> = std::env::args().collect();
-    let mut clients: HashMap<u16, Client> = HashMap::with_capacity(usize::try_from(u16::MAX)?);
-    let records_path = &args[1];
+// Command line arguments.
+struct Args {
+    records_path: String,
+    summary: bool,
+    fail_on_reject: bool,
+    json_warnings: bool,
+    validate_only: bool,
+    merge_output: Option<String>,
+    threads: usize,
+    output: OutputOptions,
+}
 
-    let transaction_records = csv::ReaderBuilder::new()
-        .trim(csv::Trim::All)
-        .flexible(true)
-        .from_path(records_path)?;
+impl Args {
+    // Parse the command line arguments, flags may appear in any order.
+    fn parse() -> Result<Self, Box<dyn Error>> {
+        let mut args = std::env::args().skip(1);
+        let mut records_path = None;
+        let mut summary = false;
+        let mut fail_on_reject = false;
+        let mut json_warnings = false;
+        let mut validate_only = false;
+        let mut merge_output = None;
+        let mut threads = 1;
+        let mut output = OutputOptions::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--summary" => summary = true,
+                "--fail-on-reject" => fail_on_reject = true,
+                "--json-warnings" => json_warnings = true,
+                "--validate-only" => validate_only = true,
+                "--computed-total" => output.computed_total = true,
+                "--only-active" => output.only_active = true,
+                "--omit-empty-header" => output.omit_empty_header = true,
+                "--threads" => {
+                    let count = args.next().ok_or("--threads requires a count")?;
+                    threads = count.parse()?;
+                }
+                "--merge-output" => {
+                    let path = args.next().ok_or("--merge-output requires a file")?;
+                    merge_output = Some(path);
+                }
+                "--scale" => {
+                    let scale = args.next().ok_or("--scale requires a scale")?;
+                    output.scale = Some(scale.parse()?);
+                }
+                "--fixed-width" => {
+                    let width = args.next().ok_or("--fixed-width requires a width")?;
+                    output.fixed_width = Some(width.parse()?);
+                }
+                "--minor-units" => {
+                    let scale = args.next().ok_or("--minor-units requires a scale")?;
+                    output.minor_units = Some(scale.parse()?);
+                }
+                flag if flag.starts_with("--") => return Err(format!("unknown flag {flag}").into()),
+                _ => records_path = Some(arg),
+            }
+        }
+
+        Ok(Args {
+            records_path: records_path.ok_or("missing transactions file argument")?,
+            summary,
+            fail_on_reject,
+            json_warnings,
+            validate_only,
+            merge_output,
+            threads,
+            output,
+        })
+    }
+}
+
+// Errors are printed without the `Debug` formatting of returning them from
+// `main`.
+fn main() {
+    if let Err(err) = Args::parse().and_then(run) {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let directory = Path::new(&args.records_path).is_dir();
+    let inputs = inputs(&args.records_path)?;
+    if args.validate_only {
+        return validate(&inputs, directory);
+    }
+
+    let engine = if args.threads > 1 {
+        if args.merge_output.is_some() {
+            return Err("--merge-output cannot be combined with --threads".into());
+        }
+        if directory {
+            return Err("--threads cannot be combined with an input directory".into());
+        }
+        run_parallel(open(&inputs[0])?, args.threads, || {
+            engine(&args.output)
+                .warnings_enabled(args.json_warnings)
+                .lookup_path(&args.records_path)
+        })?
+    } else {
+        let mut engine = engine(&args.output).warnings_enabled(args.json_warnings);
+        if let Some(merge_output) = &args.merge_output {
+            engine = engine.with_clients(load(merge_output)?);
+        }
+        // A file is processed as `run` does, disputes reading the file for
+        // the disputed transaction. Files of a directory share the index of
+        // the engine instead so disputes may refer to earlier files.
+        if !directory {
+            engine = engine.lookup_path(&args.records_path);
+        }
+        for input in &inputs {
+            engine.process(open(input)?)?;
+        }
+        engine
+    };
+    let rejected = engine.stats().rejected();
+    let reused_tx = engine.stats().reused_tx;
+    if args.json_warnings {
+        for warning in engine.warnings() {
+            eprintln!(
+                r#"{{"level":"warn","tx":{},"reason":"{}"}}"#,
+                warning.tx, warning.reason
+            );
+        }
+        if reused_tx > 0 {
+            eprintln!(r#"{{"level":"warn","reason":"reused_tx","count":{reused_tx}}}"#);
+        }
+    } else if reused_tx > 0 {
+        eprintln!("warning: {reused_tx} transaction IDs reused across deposits and withdrawals");
+    }
+
+    if args.summary {
+        let mut wtr = csv::Writer::from_writer(std::io::stdout());
+        wtr.serialize(summarize(&engine.into_clients())?)?;
+        wtr.flush()?;
+    } else {
+        engine.write(std::io::stdout())?;
+    }
+
+    // The output is still written so the run can be inspected.
+    if args.fail_on_reject && rejected > 0 {
+        return Err(format!("{rejected} transactions rejected").into());
+    }
+
+    Ok(())
+}
+
+// Build an `Engine` writing clients with `output`.
+fn engine(output: &OutputOptions) -> Engine {
+    let mut engine = Engine::new();
+    if let Some(scale) = output.minor_units {
+        engine = engine.minor_units(scale);
+    }
+    if let Some(scale) = output.scale {
+        engine = engine.with_scale(scale);
+    }
+    if let Some(width) = output.fixed_width {
+        engine = engine.fixed_width(width);
+    }
+    engine
+        .computed_total(output.computed_total)
+        .only_active(output.only_active)
+        .omit_empty_header(output.omit_empty_header)
+}
 
-    run(&mut clients, transaction_records, records_path)?;
+// Print every issue found in the records, failing if there are any. Issues
+// are prefixed by their file when the input is a `directory`.
+fn validate(inputs: &[PathBuf], directory: bool) -> Result<(), Box<dyn Error>> {
+    let mut count = 0;
+    for input in inputs {
+        let issues = run_validate(open(input)?);
+        for issue in &issues {
+            if directory {
+                println!("{}: {issue}", input.display());
+            } else {
+                println!("{issue}");
+            }
+        }
+        count += issues.len();
+    }
 
-    let mut wtr = csv::Writer::from_writer(std::io::stdout());
-    for client in clients.values() {
-        wtr.serialize(client)?;
+    if count > 0 {
+        return Err(format!("{count} issues found").into());
     }
-    wtr.flush()?;
 
     Ok(())
 }
+
+// Returns the input files at `records_path`, every `.csv` file in sorted
+// filename order if it is a directory.
+fn inputs(records_path: &str) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let path = Path::new(records_path);
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let entries = std::fs::read_dir(path)
+        .map_err(|err| format!("cannot read input directory {records_path}: {err}"))?;
+    let mut inputs = vec![];
+    for entry in entries {
+        let input = entry?.path();
+        if input.is_file()
+            && input
+                .extension()
+                .is_some_and(|extension| extension == "csv")
+        {
+            inputs.push(input);
+        }
+    }
+    inputs.sort();
+
+    Ok(inputs)
+}
+
+// Load the clients of a previous run's output at `output_path`.
+fn load(output_path: &str) -> Result<HashMap<u16, Client>, Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(output_path)
+        .map_err(|err| format!("cannot open output file {output_path}: {err}"))?;
+    let snapshots = reader
+        .deserialize::<ClientSnapshot>()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(load_clients(snapshots)?)
+}
+
+// Open the records at `records_path`, naming the file in any error.
+fn open(records_path: &Path) -> Result<csv::Reader<RecordLimit<File>>, Box<dyn Error>> {
+    let file = File::open(records_path)
+        .map_err(|err| format!("cannot open input file {}: {err}", records_path.display()))?;
+    Ok(ReaderConfig::default().from_reader(file))
+}
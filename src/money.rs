@@ -0,0 +1,155 @@
+//! Amount types balances are kept in.
+//!
+//! Amounts are read and reported as `Decimal`, an `Engine` keeps its balances
+//! in any `Money` type. `Decimal` is the default, `MinorUnits` keeps integer
+//! counts of ten-thousandths for embedders preferring exact integer math.
+
+use std::fmt::Debug;
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::DEFAULT_SCALE;
+
+/// An amount of funds as kept in a `Balance`.
+///
+/// Amounts are converted from `Decimal` as applied to an account and back to
+/// `Decimal` when read, the balance math is done in the implementing type.
+/// Operations overflowing the type are refused rather than applied.
+pub trait Money: Debug + Default + Copy + Ord {
+    /// Returns zero funds at `scale` decimal places, where the type keeps a
+    /// scale.
+    fn zero(scale: u32) -> Self;
+
+    /// Converts `amount`, or returns `None` if it cannot be represented
+    /// exactly.
+    fn from_decimal(amount: Decimal) -> Option<Self>;
+
+    /// Converts this amount to a `Decimal`.
+    fn to_decimal(self) -> Decimal;
+
+    /// Returns the sum of both amounts, or `None` on overflow.
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// Returns the difference of both amounts, or `None` on overflow.
+    fn checked_sub(self, other: Self) -> Option<Self>;
+
+    /// Returns the negated amount, or `None` on overflow.
+    fn checked_neg(self) -> Option<Self>;
+}
+
+impl Money for Decimal {
+    fn zero(scale: u32) -> Self {
+        Decimal::new(0, scale)
+    }
+
+    fn from_decimal(amount: Decimal) -> Option<Self> {
+        Some(amount)
+    }
+
+    fn to_decimal(self) -> Decimal {
+        self
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Decimal::checked_add(self, other)
+    }
+
+    fn checked_sub(self, other: Self) -> Option<Self> {
+        Decimal::checked_sub(self, other)
+    }
+
+    fn checked_neg(self) -> Option<Self> {
+        Some(-self)
+    }
+}
+
+/// An integer count of minor units of ten-thousandths, the default scale.
+///
+/// Amounts with more than four decimal places or beyond the range of `i64`
+/// cannot be represented and are refused as invalid amounts, round deposits
+/// and withdrawals with `Engine::rounding` to apply them. Operations
+/// overflowing `i64` are refused with `OpResult::SkippedOverflow`.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct MinorUnits(i64);
+
+impl MinorUnits {
+    /// Decimal places of a minor unit.
+    pub const SCALE: u32 = DEFAULT_SCALE;
+
+    /// Create an amount of `units` minor units.
+    pub fn new(units: i64) -> Self {
+        MinorUnits(units)
+    }
+
+    /// Returns the number of minor units.
+    pub fn units(self) -> i64 {
+        self.0
+    }
+}
+
+impl Money for MinorUnits {
+    fn zero(_scale: u32) -> Self {
+        MinorUnits(0)
+    }
+
+    fn from_decimal(amount: Decimal) -> Option<Self> {
+        let units = amount.checked_mul(Decimal::new(10_i64.pow(Self::SCALE), 0))?;
+        if !units.fract().is_zero() {
+            return None;
+        }
+        units.to_i64().map(MinorUnits)
+    }
+
+    fn to_decimal(self) -> Decimal {
+        Decimal::new(self.0, Self::SCALE)
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(MinorUnits)
+    }
+
+    fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(MinorUnits)
+    }
+
+    fn checked_neg(self) -> Option<Self> {
+        self.0.checked_neg().map(MinorUnits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minor_units_from_decimal() {
+        assert_eq!(
+            MinorUnits::from_decimal(Decimal::new(15, 1)),
+            Some(MinorUnits::new(15000))
+        );
+        assert_eq!(
+            MinorUnits::from_decimal(Decimal::new(-1, 4)),
+            Some(MinorUnits::new(-1))
+        );
+        assert_eq!(MinorUnits::from_decimal(Decimal::new(1, 5)), None);
+        assert_eq!(MinorUnits::from_decimal(Decimal::MAX), None);
+    }
+
+    #[test]
+    fn minor_units_to_decimal() {
+        assert_eq!(MinorUnits::new(15000).to_decimal(), Decimal::new(15, 1));
+        assert_eq!(MinorUnits::new(15000).to_decimal().scale(), 4);
+    }
+
+    #[test]
+    fn minor_units_checked_math() {
+        let one = MinorUnits::new(1);
+        assert_eq!(one.checked_add(one), Some(MinorUnits::new(2)));
+        assert_eq!(one.checked_sub(one), Some(MinorUnits::new(0)));
+        assert_eq!(one.checked_neg(), Some(MinorUnits::new(-1)));
+        assert_eq!(MinorUnits::new(i64::MAX).checked_add(one), None);
+        assert_eq!(MinorUnits::new(i64::MIN).checked_sub(one), None);
+        assert_eq!(MinorUnits::new(i64::MIN).checked_neg(), None);
+    }
+}
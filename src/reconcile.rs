@@ -0,0 +1,35 @@
+//! Differences between two sets of client accounts.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::client::{Client, ClientSnapshot};
+
+/// A client whose account differs between two sets of clients.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ClientDiff {
+    /// Client ID.
+    pub client: u16,
+    /// The client in the first set, `None` if absent.
+    pub left: Option<ClientSnapshot>,
+    /// The client in the second set, `None` if absent.
+    pub right: Option<ClientSnapshot>,
+}
+
+/// List the clients that differ between `a` and `b` sorted by Client ID.
+///
+/// Clients differ if any balance or the lock state differs, balances are
+/// compared numerically. Clients present in only one set are also listed.
+pub fn reconcile(a: &HashMap<u16, Client>, b: &HashMap<u16, Client>) -> Vec<ClientDiff> {
+    let ids: BTreeSet<u16> = a.keys().chain(b.keys()).copied().collect();
+
+    ids.into_iter()
+        .filter_map(|id| match (a.get(&id), b.get(&id)) {
+            (Some(left), Some(right)) if left.balances_equal(right) => None,
+            (left, right) => Some(ClientDiff {
+                client: id,
+                left: left.map(ClientSnapshot::from),
+                right: right.map(ClientSnapshot::from),
+            }),
+        })
+        .collect()
+}
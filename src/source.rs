@@ -0,0 +1,40 @@
+//! Sources of transaction records.
+//!
+//! `RecordSource` decouples processing from `csv` so records may also be read
+//! from other sources such as a database cursor.
+
+use crate::error::KoncordError;
+use crate::transaction::Record;
+use crate::{default_headers, deserialize_record};
+
+/// A source of transaction records read in order.
+pub trait RecordSource {
+    /// Returns the next record or `None` once the source is exhausted.
+    fn next_record(&mut self) -> Result<Option<Record>, KoncordError>;
+}
+
+/// Records are validated as by `Engine::process`, blank lines are skipped.
+/// Failures to read or deserialize a record are reported as
+/// `KoncordError::Io` or `KoncordError::Csv`.
+impl<R: std::io::Read> RecordSource for csv::Reader<R> {
+    fn next_record(&mut self) -> Result<Option<Record>, KoncordError> {
+        let mut raw = csv::StringRecord::new();
+        loop {
+            if !self.read_record(&mut raw)? {
+                return Ok(None);
+            }
+            // Lines of only whitespace are blank once trimmed.
+            if raw.iter().any(|field| !field.is_empty()) {
+                break;
+            }
+        }
+
+        let record = if self.has_headers() {
+            let headers = self.headers()?;
+            deserialize_record(&raw, headers, None)
+        } else {
+            deserialize_record(&raw, &default_headers(), None)
+        };
+        Ok(Some(record?))
+    }
+}
@@ -0,0 +1,97 @@
+//! Aggregate statistics and per-client reports across all client accounts.
+
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::client::Client;
+use crate::error::KoncordError;
+
+/// Totals across all clients.
+///
+/// Serializes as a single CSV row in place of the per-client output.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct Summary {
+    /// Number of clients.
+    pub clients: usize,
+    /// Number of locked accounts.
+    pub locked: usize,
+    /// Sum of available funds.
+    pub available: Decimal,
+    /// Sum of held funds.
+    pub held: Decimal,
+    /// Sum of total funds.
+    pub total: Decimal,
+}
+
+/// Summarize the balances of all `clients`.
+///
+/// Fails with `KoncordError::SummaryOverflow` if a sum exceeds
+/// `Decimal::MAX`, each balance may be valid while their sum is not.
+pub fn summarize(clients: &HashMap<u16, Client>) -> Result<Summary, KoncordError> {
+    let mut summary = Summary {
+        clients: clients.len(),
+        locked: 0,
+        available: Decimal::ZERO,
+        held: Decimal::ZERO,
+        total: Decimal::ZERO,
+    };
+
+    for client in clients.values() {
+        let account = client.get();
+        if account.is_locked() {
+            summary.locked += 1;
+        }
+        let add = |sum: Decimal, funds: Decimal| {
+            sum.checked_add(funds).ok_or(KoncordError::SummaryOverflow)
+        };
+        summary.available = add(summary.available, account.available())?;
+        summary.held = add(summary.held, account.held())?;
+        summary.total = add(summary.total, account.total())?;
+    }
+
+    Ok(summary)
+}
+
+/// Activity of a single client.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct TransactionReport {
+    /// Client ID.
+    pub client: u16,
+    /// Number of deposits applied.
+    pub deposits: usize,
+    /// Number of withdrawals applied.
+    pub withdrawals: usize,
+    /// Number of disputes applied.
+    pub disputes: usize,
+    /// Sum of deposits applied.
+    pub deposited: Decimal,
+    /// Sum of withdrawals applied.
+    pub withdrawn: Decimal,
+    /// The account has been locked by a chargeback.
+    pub locked: bool,
+}
+
+/// Report the activity of all `clients` sorted by Client ID.
+pub fn report(clients: &HashMap<u16, Client>) -> Vec<TransactionReport> {
+    let mut reports: Vec<TransactionReport> = clients
+        .values()
+        .map(|client| {
+            let account = client.get();
+            let activity = account.activity();
+            TransactionReport {
+                client: client.id(),
+                deposits: activity.deposits,
+                withdrawals: activity.withdrawals,
+                disputes: activity.disputes,
+                deposited: activity.deposited,
+                withdrawn: activity.withdrawn,
+                locked: account.is_locked(),
+            }
+        })
+        .collect();
+    reports.sort_unstable_by_key(|report| report.client);
+
+    reports
+}
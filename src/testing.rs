@@ -0,0 +1,44 @@
+//! Utilities for testing output.
+//!
+//! Balances are written with the scale of the amounts applied so `1.5` and
+//! `1.5000` may both appear for the same balance. Comparing parsed rows keeps
+//! tests independent of the textual scale.
+
+#[cfg(feature = "testing")]
+use crate::client::Client;
+use crate::client::ClientSnapshot;
+
+/// Assert the client rows of `actual_csv` equal those of `expected_csv`.
+///
+/// Both are parsed as `ClientSnapshot` rows with headers and compared in
+/// order, balances numerically.
+///
+/// # Panics
+///
+/// Panics if either fails to parse or any row differs.
+pub fn assert_balances_eq(actual_csv: &str, expected_csv: &str) {
+    let actual = parse(actual_csv);
+    let expected = parse(expected_csv);
+
+    assert_eq!(actual, expected, "client balances differ");
+}
+
+/// Build a client from `snapshot` without checking that its total is its
+/// available plus held funds.
+///
+/// Corrupt clients exercise the checks that `load_clients` would otherwise
+/// refuse to construct. Requires the `testing` feature so release builds
+/// cannot bypass those checks.
+#[cfg(feature = "testing")]
+pub fn corrupt_client(snapshot: ClientSnapshot) -> Client {
+    Client::from_snapshot_unchecked(snapshot)
+}
+
+// Parse client rows, trimming whitespace as for transaction records.
+fn parse(csv: &str) -> Vec<ClientSnapshot> {
+    crate::reader_builder()
+        .from_reader(csv.as_bytes())
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .expect("client rows should parse")
+}
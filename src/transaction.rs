@@ -1,16 +1,23 @@
 //! Transactions provides a state machine to process records to completion.
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::error::Error;
 
 use rust_decimal::Decimal;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::client::Account;
+use crate::client::{Account, AccountPolicy, OpResult};
+use crate::error::{KoncordError, RecordError};
+use crate::money::Money;
+use crate::DEFAULT_SCALE;
 
 /// Transaction record.
 ///
 /// The representation of a raw transaction record as received by the service.
-#[derive(Debug, Deserialize)]
+/// Records serialize back to the same representation, always writing the
+/// description and timestamp columns, empty when unset.
+#[derive(Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub struct Record {
     /// Transaction Type.
     #[serde(rename = "type")]
@@ -22,10 +29,45 @@ pub struct Record {
     /// Transaction amount.
     ///
     /// A decimal value with a precision of up to four places past the decimal.
+    /// Amounts are positive magnitudes, the transaction type determines
+    /// whether funds are credited or debited so a withdrawal of `1.0` debits
+    /// one unit. Negative amounts are rejected with
+    /// `KoncordError::NegativeAmount` except for adjustments which are signed.
     amount: Option<Decimal>,
+    /// Free text memo, kept for audit and ignored by balance calculations.
+    #[serde(default)]
+    description: Option<String>,
+    /// Time the transaction occurred, in seconds since the Unix epoch.
+    #[serde(default)]
+    timestamp: Option<u64>,
 }
 
 impl Record {
+    /// Create a new `Record` of `kind` for Client ID `client`.
+    pub fn new(kind: TransactionKind, client: u16, tx: u32, amount: Option<Decimal>) -> Self {
+        Record {
+            kind,
+            client,
+            tx,
+            amount,
+            description: None,
+            timestamp: None,
+        }
+    }
+
+    /// Attach a free text memo `description` to this record.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Attach the `timestamp` the transaction occurred, in seconds since the
+    /// Unix epoch.
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
     pub fn client_id(&self) -> u16 {
         self.client
     }
@@ -37,17 +79,185 @@ impl Record {
     pub fn amount(&self) -> Option<Decimal> {
         self.amount
     }
+
+    pub fn kind(&self) -> &TransactionKind {
+        &self.kind
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn timestamp(&self) -> Option<u64> {
+        self.timestamp
+    }
+
+    /// Checks the record is well formed on its own.
+    ///
+    /// Deposits, withdrawals, adjustments, holds, releases, fees and
+    /// corrections require an amount while disputes, chargebacks and
+    /// reversals refer to another transaction so take none, resolves may
+    /// release part of a dispute with one. Amounts are non-negative for all
+    /// but adjustments and corrections and have at most `DEFAULT_SCALE`
+    /// decimal places. Records of unknown types are accepted as they are
+    /// skipped when processing.
+    ///
+    /// Client and Transaction IDs are not checked, their `u16` and `u32`
+    /// types already bound them so records with IDs out of range fail to
+    /// deserialize.
+    pub fn validate(&self) -> Result<(), RecordError> {
+        if let TransactionKind::Unknown(_) = self.kind {
+            return Ok(());
+        }
+        let kind = self.kind.clone();
+        let refers = matches!(
+            kind,
+            TransactionKind::Dispute | TransactionKind::Chargeback | TransactionKind::Reversal
+        );
+        let amount = match self.amount {
+            Some(amount) if refers => {
+                return Err(RecordError::UnexpectedAmount { kind, amount });
+            }
+            Some(amount) => amount,
+            None if refers || kind == TransactionKind::Resolve => return Ok(()),
+            None => return Err(RecordError::MissingAmount { kind }),
+        };
+
+        if amount < Decimal::ZERO && !kind.is_signed() {
+            return Err(RecordError::NegativeAmount { kind, amount });
+        }
+        if amount.normalize().scale() > DEFAULT_SCALE {
+            let scale = DEFAULT_SCALE;
+            return Err(RecordError::ExcessPrecision { amount, scale });
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a transaction amount.
+///
+/// Only plain decimal notation is accepted. Non-finite tokens such as `inf`
+/// or `nan`, exponents such as `1e1000` and values beyond the precision of
+/// `Decimal` are rejected rather than approximated.
+pub(crate) fn parse_amount(value: &str) -> Option<Decimal> {
+    Decimal::from_str_exact(value).ok()
+}
+
+/// Strip surrounding quotes, a leading `+` and the `currency` symbol from an
+/// amount.
+///
+/// Quotes are only removed by the CSV reader when they open the field, so
+/// `deposit, 1, 1, "100.00"` keeps them once trimmed. A leading `-` is kept,
+/// so `-$1.0` becomes `-1.0`, for the negative amount checks.
+pub(crate) fn normalize_amount(value: &str, currency: Option<char>) -> Cow<'_, str> {
+    let value = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .map_or(value, str::trim);
+    let (negative, unsigned) = match value.as_bytes().first() {
+        Some(b'+') => (false, &value[1..]),
+        Some(b'-') => (true, &value[1..]),
+        _ => (false, value),
+    };
+    let digits = currency
+        .and_then(|symbol| unsigned.strip_prefix(symbol))
+        .unwrap_or(unsigned);
+
+    match (negative, digits.len() == unsigned.len()) {
+        (true, true) => Cow::Borrowed(value),
+        (true, false) => Cow::Owned(format!("-{digits}")),
+        (false, _) => Cow::Borrowed(digits),
+    }
 }
 
 /// Types of transactions.
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "lowercase")]
+///
+/// Serialized as the lowercase variant name.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum TransactionKind {
     Deposit,
     Withdrawal,
+    /// Manual credit or debit by support, the amount is signed.
+    Adjustment,
+    /// Administrative hold moving the amount from available to held.
+    Hold,
+    /// Release of the hold with the same Transaction ID, moving up to the
+    /// amount from held back to available.
+    Release,
+    /// Service fee debited from the available funds, which may overdraw the
+    /// account under `FeePolicy::Overdraft`.
+    Fee,
     Dispute,
     Resolve,
     Chargeback,
+    /// Cancels the deposit or withdrawal with the same Transaction ID by
+    /// applying its inverse, such as when a bank rejects the original.
+    /// Reversed deposits can no longer be disputed.
+    Reversal,
+    /// Signed correction of the deposit or withdrawal with the same
+    /// Transaction ID, such as a fee credited back on a partial recovery.
+    /// Refused unless the referenced transaction exists.
+    Correction,
+    /// A type not known to this version, records are skipped.
+    Unknown(String),
+}
+
+impl TransactionKind {
+    /// Returns the name of the transaction type as serialized.
+    pub fn as_str(&self) -> &str {
+        match self {
+            TransactionKind::Deposit => "deposit",
+            TransactionKind::Withdrawal => "withdrawal",
+            TransactionKind::Adjustment => "adjustment",
+            TransactionKind::Hold => "hold",
+            TransactionKind::Release => "release",
+            TransactionKind::Fee => "fee",
+            TransactionKind::Dispute => "dispute",
+            TransactionKind::Resolve => "resolve",
+            TransactionKind::Chargeback => "chargeback",
+            TransactionKind::Reversal => "reversal",
+            TransactionKind::Correction => "correction",
+            TransactionKind::Unknown(kind) => kind,
+        }
+    }
+
+    /// Returns `true` for the types whose amount is signed, adjustments and
+    /// corrections.
+    pub fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            TransactionKind::Adjustment | TransactionKind::Correction
+        )
+    }
+}
+
+impl Serialize for TransactionKind {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+// Unknown types deserialize to `TransactionKind::Unknown` rather than failing
+// so newer inputs can still be processed.
+impl<'de> Deserialize<'de> for TransactionKind {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let kind = String::deserialize(deserializer)?;
+        Ok(match kind.as_str() {
+            "deposit" => TransactionKind::Deposit,
+            "withdrawal" => TransactionKind::Withdrawal,
+            "adjustment" => TransactionKind::Adjustment,
+            "hold" => TransactionKind::Hold,
+            "release" => TransactionKind::Release,
+            "fee" => TransactionKind::Fee,
+            "dispute" => TransactionKind::Dispute,
+            "resolve" => TransactionKind::Resolve,
+            "chargeback" => TransactionKind::Chargeback,
+            "reversal" => TransactionKind::Reversal,
+            "correction" => TransactionKind::Correction,
+            _ => TransactionKind::Unknown(kind),
+        })
+    }
 }
 
 /// A single transaction.
@@ -59,34 +269,105 @@ pub struct Transaction<S> {
 }
 
 impl Transaction<Received> {
+    pub fn id(&self) -> u32 {
+        self.state.id
+    }
+
     pub fn kind(&self) -> &TransactionKind {
         &self.state.kind
     }
+
+    pub fn amount(&self) -> Option<Decimal> {
+        self.state.amount
+    }
 }
 
 impl Transaction<Processing> {
-    fn new(kind: TransactionKind, amount: Decimal) -> Self {
+    fn new(kind: TransactionKind, tx: u32, amount: Decimal) -> Self {
         Transaction {
-            state: Processing::new(kind, amount),
+            state: Processing::new(kind, tx, amount),
         }
     }
 
-    pub fn process(self, account: &mut Account) -> Transaction<Completed> {
-        match self.state.kind {
-            TransactionKind::Deposit => account.deposit(self.state.amount),
-            TransactionKind::Withdrawal => account.withdraw(self.state.amount),
-            TransactionKind::Dispute => account.dispute(self.state.amount),
-            TransactionKind::Resolve => account.resolve(self.state.amount),
-            TransactionKind::Chargeback => account.chargeback(self.state.amount),
+    pub fn amount(&self) -> Decimal {
+        self.state.amount
+    }
+
+    /// Apply the account operation, transitioning to `Completed` if applied
+    /// or clamped and to `Failed` if refused.
+    pub fn process<M: Money>(
+        self,
+        account: &mut Account<M>,
+    ) -> Result<Transaction<Completed>, Transaction<Failed>> {
+        self.process_with_policy(account, &AccountPolicy::default())
+    }
+
+    /// Process as `process`, applying the account operation under `policy`.
+    pub fn process_with_policy<M: Money>(
+        self,
+        account: &mut Account<M>,
+        policy: &AccountPolicy,
+    ) -> Result<Transaction<Completed>, Transaction<Failed>> {
+        let Processing { kind, tx, amount } = self.state;
+        let result = match kind {
+            TransactionKind::Dispute => account.dispute_with_policy(tx, amount, policy.dispute),
+            TransactionKind::Chargeback => account.chargeback_with_policy(
+                tx,
+                amount,
+                policy.chargeback,
+                policy.excess_chargeback,
+            ),
+            TransactionKind::Deposit | TransactionKind::Withdrawal => {
+                let scale = policy.scale.unwrap_or(DEFAULT_SCALE);
+                account.apply(&kind, tx, policy.rounding.round(amount, scale))
+            }
+            TransactionKind::Fee => account.fee_with_policy(amount, policy.fee),
+            kind => account.apply(&kind, tx, amount),
+        };
+        if policy.saturating {
+            account.saturate();
         }
 
-        Transaction::<Completed>::new()
+        match FailureReason::from_result(result) {
+            Some(reason) => Err(Transaction::<Failed>::new(reason)),
+            None => Ok(Transaction::<Completed>::new(result)),
+        }
     }
 }
 
 impl Transaction<Completed> {
-    fn new() -> Self {
-        Transaction { state: Completed }
+    fn new(result: OpResult) -> Self {
+        Transaction {
+            state: Completed { result },
+        }
+    }
+
+    pub fn result(&self) -> OpResult {
+        self.state.result
+    }
+}
+
+impl Transaction<Failed> {
+    fn new(reason: FailureReason) -> Self {
+        Transaction {
+            state: Failed { reason },
+        }
+    }
+
+    pub fn reason(&self) -> FailureReason {
+        self.state.reason
+    }
+}
+
+impl From<Transaction<Completed>> for OpResult {
+    fn from(completed: Transaction<Completed>) -> Self {
+        completed.result()
+    }
+}
+
+impl From<Transaction<Failed>> for OpResult {
+    fn from(failed: Transaction<Failed>) -> Self {
+        failed.reason().into()
     }
 }
 
@@ -107,9 +388,37 @@ impl Transaction<DisputeLookup> {
 }
 
 impl Transaction<Resolved> {
+    fn new(tx: u32, amount: Option<Decimal>) -> Self {
+        Transaction {
+            state: Resolved::new(tx, amount),
+        }
+    }
+
+    pub fn tx(&self) -> u32 {
+        self.state.tx
+    }
+
+    /// Release the requested portion of the `held` dispute amount.
+    ///
+    /// Resolves without an amount, or with an amount that is not positive or
+    /// not less than `held`, release the full held amount so successive
+    /// partial resolves never release more than was held. Returns the amount
+    /// that remains held.
+    pub fn release(&mut self, held: Decimal) -> Decimal {
+        let amount = match self.state.amount {
+            Some(amount) if amount > Decimal::ZERO && amount < held => amount,
+            _ => held,
+        };
+        self.state.amount = Some(amount);
+
+        held - amount
+    }
+}
+
+impl Transaction<Reversed> {
     fn new(tx: u32) -> Self {
         Transaction {
-            state: Resolved::new(tx),
+            state: Reversed::new(tx),
         }
     }
 
@@ -117,8 +426,27 @@ impl Transaction<Resolved> {
         self.state.tx
     }
 
-    pub fn set_amount(&mut self, amount: Option<Decimal>) {
-        self.state.amount = amount;
+    /// Set the `kind` and `amount` of the original transaction to reverse.
+    pub fn set_original(&mut self, kind: TransactionKind, amount: Decimal) {
+        self.state.original = Some((kind, amount));
+    }
+}
+
+impl Transaction<Corrected> {
+    fn new(tx: u32, amount: Decimal) -> Self {
+        Transaction {
+            state: Corrected::new(tx, amount),
+        }
+    }
+
+    pub fn tx(&self) -> u32 {
+        self.state.tx
+    }
+
+    /// Mark the referenced transaction as found, without which the
+    /// correction cannot be processed.
+    pub fn set_referenced(&mut self) {
+        self.state.referenced = true;
     }
 }
 
@@ -150,24 +478,91 @@ pub struct Received {
 #[derive(Debug, Clone)]
 pub struct Processing {
     kind: TransactionKind,
-    pub amount: Decimal,
+    tx: u32,
+    amount: Decimal,
 }
 
 impl Processing {
-    fn new(kind: TransactionKind, amount: Decimal) -> Self {
-        Processing { kind, amount }
+    fn new(kind: TransactionKind, tx: u32, amount: Decimal) -> Self {
+        Processing { kind, tx, amount }
     }
 }
 
-/// Result of succecefully processing a deposit or withdrawal transaction.
+/// Transaction applied to an account, the terminal state of a success.
 #[derive(Debug, Clone)]
-pub struct Completed;
+pub struct Completed {
+    result: OpResult,
+}
+
+/// Transaction refused by the account, the terminal state of a failure.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Failed {
+    reason: FailureReason,
+}
+
+/// Why the account refused a transaction, the refusals of `OpResult`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FailureReason {
+    /// The account is locked.
+    AccountLocked,
+    /// The amount was not positive.
+    InvalidAmount,
+    /// The account lacks the available funds.
+    InsufficientFunds,
+    /// The account lacks the available funds but its held funds would have
+    /// covered the withdrawal.
+    FundsHeld,
+    /// The chargeback exceeded the total funds.
+    ExceedsTotal,
+    /// The transaction type is unknown.
+    UnknownKind,
+    /// The operation cannot be undone.
+    Irreversible,
+    /// The hold or release refers to a transaction with an open dispute.
+    Disputed,
+    /// The operation would overflow the funds of the balance.
+    Overflow,
+}
+
+impl FailureReason {
+    // Returns the reason `result` was refused, `None` if it was applied.
+    fn from_result(result: OpResult) -> Option<Self> {
+        match result {
+            OpResult::Applied | OpResult::Clamped => None,
+            OpResult::SkippedLocked => Some(FailureReason::AccountLocked),
+            OpResult::SkippedInvalidAmount => Some(FailureReason::InvalidAmount),
+            OpResult::SkippedInsufficientFunds => Some(FailureReason::InsufficientFunds),
+            OpResult::SkippedFundsHeld => Some(FailureReason::FundsHeld),
+            OpResult::SkippedExceedsTotal => Some(FailureReason::ExceedsTotal),
+            OpResult::SkippedUnknownKind => Some(FailureReason::UnknownKind),
+            OpResult::SkippedIrreversible => Some(FailureReason::Irreversible),
+            OpResult::SkippedDisputed => Some(FailureReason::Disputed),
+            OpResult::SkippedOverflow => Some(FailureReason::Overflow),
+        }
+    }
+}
+
+impl From<FailureReason> for OpResult {
+    fn from(reason: FailureReason) -> Self {
+        match reason {
+            FailureReason::AccountLocked => OpResult::SkippedLocked,
+            FailureReason::InvalidAmount => OpResult::SkippedInvalidAmount,
+            FailureReason::InsufficientFunds => OpResult::SkippedInsufficientFunds,
+            FailureReason::FundsHeld => OpResult::SkippedFundsHeld,
+            FailureReason::ExceedsTotal => OpResult::SkippedExceedsTotal,
+            FailureReason::UnknownKind => OpResult::SkippedUnknownKind,
+            FailureReason::Irreversible => OpResult::SkippedIrreversible,
+            FailureReason::Disputed => OpResult::SkippedDisputed,
+            FailureReason::Overflow => OpResult::SkippedOverflow,
+        }
+    }
+}
 
 /// Disputed transaction needs to be looked up for amount of funds to hold.
 #[derive(Debug, Clone)]
 pub struct DisputeLookup {
     tx: u32,
-    pub amount: Option<Decimal>,
+    amount: Option<Decimal>,
 }
 
 impl DisputeLookup {
@@ -176,7 +571,7 @@ impl DisputeLookup {
     }
 }
 
-/// Dispute is resolved, held funds are released.
+/// Dispute is resolved, all or part of the held funds are released.
 #[derive(Debug, Clone)]
 pub struct Resolved {
     tx: u32,
@@ -184,8 +579,40 @@ pub struct Resolved {
 }
 
 impl Resolved {
+    fn new(tx: u32, amount: Option<Decimal>) -> Self {
+        Resolved { tx, amount }
+    }
+}
+
+/// Reversed transaction needs the original looked up to apply its inverse.
+#[derive(Debug, Clone)]
+pub struct Reversed {
+    tx: u32,
+    original: Option<(TransactionKind, Decimal)>,
+}
+
+impl Reversed {
     fn new(tx: u32) -> Self {
-        Resolved { tx, amount: None }
+        Reversed { tx, original: None }
+    }
+}
+
+/// Corrected transaction needs its reference looked up to apply the signed
+/// amount.
+#[derive(Debug, Clone)]
+pub struct Corrected {
+    tx: u32,
+    amount: Decimal,
+    referenced: bool,
+}
+
+impl Corrected {
+    fn new(tx: u32, amount: Decimal) -> Self {
+        Corrected {
+            tx,
+            amount,
+            referenced: false,
+        }
     }
 }
 
@@ -214,7 +641,8 @@ impl From<Record> for Transaction<Received> {
     }
 }
 
-#[derive(Debug)]
+/// A transaction attempted a transition its state does not permit.
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct InvalidTransitionError {
     from: String,
     to: String,
@@ -232,59 +660,87 @@ impl std::fmt::Display for InvalidTransitionError {
 
 impl Error for InvalidTransitionError {}
 
+// Amounts are positive magnitudes, the transaction kind determines whether
+// funds are credited or debited.
+fn positive(tx: u32, amount: Decimal) -> Result<Decimal, KoncordError> {
+    if amount < Decimal::ZERO {
+        return Err(KoncordError::NegativeAmount { tx, amount });
+    }
+
+    Ok(amount)
+}
+
 impl TryFrom<Transaction<Received>> for Transaction<Processing> {
-    type Error = InvalidTransitionError;
+    type Error = KoncordError;
 
     fn try_from(prev: Transaction<Received>) -> Result<Self, Self::Error> {
         match prev.state.kind {
-            TransactionKind::Deposit => {
+            TransactionKind::Deposit
+            | TransactionKind::Withdrawal
+            | TransactionKind::Hold
+            | TransactionKind::Release
+            | TransactionKind::Fee => {
                 if let Some(amount) = prev.state.amount {
-                    return Ok(Transaction::<Processing>::new(prev.state.kind, amount));
+                    let amount = positive(prev.state.id, amount)?;
+                    return Ok(Transaction::<Processing>::new(
+                        prev.state.kind,
+                        prev.state.id,
+                        amount,
+                    ));
                 }
             }
-            TransactionKind::Withdrawal => {
+            // Adjustments are the only signed amounts applied directly,
+            // corrections first find their reference as `Corrected`.
+            TransactionKind::Adjustment => {
                 if let Some(amount) = prev.state.amount {
-                    return Ok(Transaction::<Processing>::new(prev.state.kind, amount));
+                    return Ok(Transaction::<Processing>::new(
+                        prev.state.kind,
+                        prev.state.id,
+                        amount,
+                    ));
                 }
             }
             kind => {
                 return Err(InvalidTransitionError {
                     from: "Transaction<Received>".to_string(),
                     to: format!("{kind:?}"),
-                })
+                }
+                .into())
             }
         };
 
         Err(InvalidTransitionError {
             from: "Transaction<Received>".to_string(),
             to: "Transaction<Processing>".to_string(),
-        })
+        }
+        .into())
     }
 }
 
 impl TryFrom<Transaction<Received>> for Transaction<DisputeLookup> {
-    type Error = InvalidTransitionError;
+    type Error = KoncordError;
 
     fn try_from(prev: Transaction<Received>) -> Result<Self, Self::Error> {
         match prev.state.kind {
             TransactionKind::Dispute => Ok(Transaction::<DisputeLookup>::new(prev.state.id)),
-            kind => {
-                return Err(InvalidTransitionError {
-                    from: "Transaction<Received>".to_string(),
-                    to: format!("{kind:?}"),
-                })
+            kind => Err(InvalidTransitionError {
+                from: "Transaction<Received>".to_string(),
+                to: format!("{kind:?}"),
             }
+            .into()),
         }
     }
 }
 
 impl TryFrom<Transaction<DisputeLookup>> for Transaction<Processing> {
-    type Error = InvalidTransitionError;
+    type Error = KoncordError;
 
     fn try_from(prev: Transaction<DisputeLookup>) -> Result<Self, Self::Error> {
         if let Some(amount) = prev.state.amount {
+            let amount = positive(prev.state.tx, amount)?;
             return Ok(Transaction::<Processing>::new(
                 TransactionKind::Dispute,
+                prev.state.tx,
                 amount,
             ));
         }
@@ -292,33 +748,123 @@ impl TryFrom<Transaction<DisputeLookup>> for Transaction<Processing> {
         Err(InvalidTransitionError {
             from: "Transaction<DisputeLookup>".to_string(),
             to: "Transaction<Processing>".to_string(),
-        })
+        }
+        .into())
+    }
+}
+
+impl TryFrom<Transaction<Received>> for Transaction<Reversed> {
+    type Error = KoncordError;
+
+    fn try_from(prev: Transaction<Received>) -> Result<Self, Self::Error> {
+        match prev.state.kind {
+            TransactionKind::Reversal => Ok(Transaction::<Reversed>::new(prev.state.id)),
+            kind => Err(InvalidTransitionError {
+                from: "Transaction<Received>".to_string(),
+                to: format!("{kind:?}"),
+            }
+            .into()),
+        }
+    }
+}
+
+// Reversals of deposits debit and reversals of withdrawals credit the amount
+// of the original.
+impl TryFrom<Transaction<Reversed>> for Transaction<Processing> {
+    type Error = KoncordError;
+
+    fn try_from(prev: Transaction<Reversed>) -> Result<Self, Self::Error> {
+        let tx = prev.state.tx;
+        match prev.state.original {
+            Some((TransactionKind::Deposit, amount)) => Ok(Transaction::<Processing>::new(
+                TransactionKind::Reversal,
+                tx,
+                -positive(tx, amount)?,
+            )),
+            Some((TransactionKind::Withdrawal, amount)) => Ok(Transaction::<Processing>::new(
+                TransactionKind::Reversal,
+                tx,
+                positive(tx, amount)?,
+            )),
+            _ => Err(InvalidTransitionError {
+                from: "Transaction<Reversed>".to_string(),
+                to: "Transaction<Processing>".to_string(),
+            }
+            .into()),
+        }
+    }
+}
+
+impl TryFrom<Transaction<Received>> for Transaction<Corrected> {
+    type Error = KoncordError;
+
+    fn try_from(prev: Transaction<Received>) -> Result<Self, Self::Error> {
+        match (prev.state.kind, prev.state.amount) {
+            (TransactionKind::Correction, Some(amount)) => {
+                Ok(Transaction::<Corrected>::new(prev.state.id, amount))
+            }
+            (kind, _) => Err(InvalidTransitionError {
+                from: "Transaction<Received>".to_string(),
+                to: format!("{kind:?}"),
+            }
+            .into()),
+        }
+    }
+}
+
+// Corrections only apply once the transaction they refer to is found.
+impl TryFrom<Transaction<Corrected>> for Transaction<Processing> {
+    type Error = KoncordError;
+
+    fn try_from(prev: Transaction<Corrected>) -> Result<Self, Self::Error> {
+        if prev.state.referenced {
+            return Ok(Transaction::<Processing>::new(
+                TransactionKind::Correction,
+                prev.state.tx,
+                prev.state.amount,
+            ));
+        }
+
+        Err(InvalidTransitionError {
+            from: "Transaction<Corrected>".to_string(),
+            to: "Transaction<Processing>".to_string(),
+        }
+        .into())
     }
 }
 
 impl TryFrom<Transaction<Received>> for Transaction<Resolved> {
-    type Error = InvalidTransitionError;
+    type Error = KoncordError;
 
     fn try_from(prev: Transaction<Received>) -> Result<Self, Self::Error> {
         match prev.state.kind {
-            TransactionKind::Resolve => Ok(Transaction::<Resolved>::new(prev.state.id)),
-            kind => {
-                return Err(InvalidTransitionError {
-                    from: "Transaction<Received>".to_string(),
-                    to: format!("{kind:?}"),
-                })
+            TransactionKind::Resolve => {
+                if let Some(amount) = prev.state.amount {
+                    positive(prev.state.id, amount)?;
+                }
+                Ok(Transaction::<Resolved>::new(
+                    prev.state.id,
+                    prev.state.amount,
+                ))
+            }
+            kind => Err(InvalidTransitionError {
+                from: "Transaction<Received>".to_string(),
+                to: format!("{kind:?}"),
             }
+            .into()),
         }
     }
 }
 
 impl TryFrom<Transaction<Resolved>> for Transaction<Processing> {
-    type Error = InvalidTransitionError;
+    type Error = KoncordError;
 
     fn try_from(prev: Transaction<Resolved>) -> Result<Self, Self::Error> {
         if let Some(amount) = prev.state.amount {
+            let amount = positive(prev.state.tx, amount)?;
             return Ok(Transaction::<Processing>::new(
                 TransactionKind::Resolve,
+                prev.state.tx,
                 amount,
             ));
         }
@@ -326,33 +872,35 @@ impl TryFrom<Transaction<Resolved>> for Transaction<Processing> {
         Err(InvalidTransitionError {
             from: "Transaction<Resolved>".to_string(),
             to: "Transaction<Processing>".to_string(),
-        })
+        }
+        .into())
     }
 }
 
 impl TryFrom<Transaction<Received>> for Transaction<ChargedBack> {
-    type Error = InvalidTransitionError;
+    type Error = KoncordError;
 
     fn try_from(prev: Transaction<Received>) -> Result<Self, Self::Error> {
         match prev.state.kind {
             TransactionKind::Chargeback => Ok(Transaction::<ChargedBack>::new(prev.state.id)),
-            kind => {
-                return Err(InvalidTransitionError {
-                    from: "Transaction<Received>".to_string(),
-                    to: format!("{kind:?}"),
-                })
+            kind => Err(InvalidTransitionError {
+                from: "Transaction<Received>".to_string(),
+                to: format!("{kind:?}"),
             }
+            .into()),
         }
     }
 }
 
 impl TryFrom<Transaction<ChargedBack>> for Transaction<Processing> {
-    type Error = InvalidTransitionError;
+    type Error = KoncordError;
 
     fn try_from(prev: Transaction<ChargedBack>) -> Result<Self, Self::Error> {
         if let Some(amount) = prev.state.amount {
+            let amount = positive(prev.state.tx, amount)?;
             return Ok(Transaction::<Processing>::new(
                 TransactionKind::Chargeback,
+                prev.state.tx,
                 amount,
             ));
         }
@@ -360,6 +908,435 @@ impl TryFrom<Transaction<ChargedBack>> for Transaction<Processing> {
         Err(InvalidTransitionError {
             from: "Transaction<ChargedBack>".to_string(),
             to: "Transaction<Processing>".to_string(),
+        }
+        .into())
+    }
+}
+
+/// A deposit or withdrawal referenced by a dispute, correction or reversal.
+#[derive(Debug, Clone)]
+pub(crate) struct Referenced {
+    pub(crate) kind: TransactionKind,
+    pub(crate) amount: Option<Decimal>,
+}
+
+// Transactions tracked across records by `dispatch`, kept in memory by an
+// `Engine` and found by re-reading the records for `run`.
+pub(crate) trait Tracker {
+    // Returns the transaction `tx` of client `id` as referenced by a record of
+    // `kind`, or `None` if it may not be referenced.
+    fn referenced(
+        &mut self,
+        kind: &TransactionKind,
+        tx: u32,
+        id: u16,
+    ) -> Result<Option<Referenced>, Box<dyn Error>>;
+
+    // Returns the funds held by each open dispute keyed by the disputed
+    // Transaction ID.
+    fn disputes(&mut self) -> &mut HashMap<u32, Decimal>;
+
+    // Notes that transaction `tx` of `kind` was applied to its account.
+    fn applied(&mut self, kind: &TransactionKind, tx: u32);
+}
+
+// What `dispatch` did with a record.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Outcome {
+    // The account operation was attempted with this result.
+    Processed(OpResult),
+    // The referenced transaction or open dispute was not found.
+    Unreferenced,
+    // The referenced transaction has an open dispute, so may be neither
+    // disputed again nor reversed.
+    Disputed,
+}
+
+// Process `received` of client `id` through the transaction states against
+// `account`, the per-kind logic shared by `Engine` and `run`.
+pub(crate) fn dispatch<M: Money, T: Tracker>(
+    received: Transaction<Received>,
+    id: u16,
+    account: &mut Account<M>,
+    policy: &AccountPolicy,
+    tracker: &mut T,
+) -> Result<Outcome, Box<dyn Error>> {
+    let (kind, tx) = (received.kind().clone(), received.id());
+    let processing = match kind {
+        TransactionKind::Dispute => {
+            let mut dispute_lookup = Transaction::<DisputeLookup>::try_from(received)?;
+            // A second dispute would overwrite the funds held by the first.
+            if tracker.disputes().contains_key(&tx) {
+                return Ok(Outcome::Disputed);
+            }
+            let Some(referenced) = tracker.referenced(&kind, tx, id)? else {
+                return Ok(Outcome::Unreferenced);
+            };
+            dispute_lookup.set_amount(referenced.amount);
+            Transaction::<Processing>::try_from(dispute_lookup)?
+        }
+        TransactionKind::Resolve => {
+            let mut resolved = Transaction::<Resolved>::try_from(received)?;
+            let Some(held) = tracker.disputes().remove(&tx) else {
+                return Ok(Outcome::Unreferenced);
+            };
+            let mut remaining = resolved.release(held);
+            let processing = Transaction::<Processing>::try_from(resolved)?;
+            let result = processing
+                .process_with_policy(account, policy)
+                .map_or_else(OpResult::from, OpResult::from);
+            // Refused resolves release nothing.
+            if result != OpResult::Applied {
+                remaining = held;
+            }
+            if remaining > Decimal::ZERO {
+                tracker.disputes().insert(tx, remaining);
+            }
+            return Ok(Outcome::Processed(result));
+        }
+        TransactionKind::Chargeback => {
+            let mut chargeback = Transaction::<ChargedBack>::try_from(received)?;
+            let Some(amount) = tracker.disputes().remove(&tx) else {
+                return Ok(Outcome::Unreferenced);
+            };
+            chargeback.set_amount(Some(amount));
+            let processing = Transaction::<Processing>::try_from(chargeback)?;
+            let result = processing
+                .process_with_policy(account, policy)
+                .map_or_else(OpResult::from, OpResult::from);
+            // Refused chargebacks leave the funds held.
+            if result == OpResult::SkippedExceedsTotal {
+                tracker.disputes().insert(tx, amount);
+            }
+            return Ok(Outcome::Processed(result));
+        }
+        TransactionKind::Correction => {
+            let mut correction = Transaction::<Corrected>::try_from(received)?;
+            if tracker.referenced(&kind, tx, id)?.is_none() {
+                return Ok(Outcome::Unreferenced);
+            }
+            correction.set_referenced();
+            Transaction::<Processing>::try_from(correction)?
+        }
+        TransactionKind::Reversal => {
+            let mut reversal = Transaction::<Reversed>::try_from(received)?;
+            let (original, amount) = match tracker.referenced(&kind, tx, id)? {
+                Some(Referenced {
+                    kind: original @ (TransactionKind::Deposit | TransactionKind::Withdrawal),
+                    amount: Some(amount),
+                }) => (original, amount),
+                _ => return Ok(Outcome::Unreferenced),
+            };
+            // Disputed deposits are resolved or charged back instead.
+            if tracker.disputes().contains_key(&tx) {
+                return Ok(Outcome::Disputed);
+            }
+            reversal.set_original(original, amount);
+            Transaction::<Processing>::try_from(reversal)?
+        }
+        // Deposits and withdrawals without an amount fail the transition.
+        _ => Transaction::<Processing>::try_from(received)?,
+    };
+
+    let amount = processing.amount();
+    let result = processing
+        .process_with_policy(account, policy)
+        .map_or_else(OpResult::from, OpResult::from);
+    if let OpResult::Applied | OpResult::Clamped = result {
+        // Refused disputes hold nothing to resolve or charge back.
+        if kind == TransactionKind::Dispute {
+            tracker.disputes().insert(tx, amount);
+        }
+        tracker.applied(&kind, tx);
+    }
+
+    Ok(Outcome::Processed(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn received(kind: TransactionKind, amount: Decimal) -> Transaction<Received> {
+        Transaction::<Received>::from(Record {
+            kind,
+            client: 1,
+            tx: 7,
+            amount: Some(amount),
+            description: None,
+            timestamp: None,
         })
     }
+
+    #[test]
+    fn validate() {
+        let record = |kind, amount| Record::new(kind, 1, 7, amount);
+        let one = Some(Decimal::ONE);
+
+        assert_eq!(record(TransactionKind::Deposit, one).validate(), Ok(()));
+        assert_eq!(record(TransactionKind::Dispute, None).validate(), Ok(()));
+        assert_eq!(record(TransactionKind::Resolve, None).validate(), Ok(()));
+        assert_eq!(record(TransactionKind::Resolve, one).validate(), Ok(()));
+        let adjustment = record(TransactionKind::Adjustment, Some(Decimal::NEGATIVE_ONE));
+        assert_eq!(adjustment.validate(), Ok(()));
+        let correction = record(TransactionKind::Correction, Some(Decimal::NEGATIVE_ONE));
+        assert_eq!(correction.validate(), Ok(()));
+        let unknown = record(TransactionKind::Unknown("refund".to_string()), None);
+        assert_eq!(unknown.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_missing_amount() {
+        let record = Record::new(TransactionKind::Withdrawal, 1, 7, None);
+        assert_eq!(
+            record.validate(),
+            Err(RecordError::MissingAmount {
+                kind: TransactionKind::Withdrawal
+            })
+        );
+    }
+
+    #[test]
+    fn validate_unexpected_amount() {
+        let record = Record::new(TransactionKind::Chargeback, 1, 7, Some(Decimal::ONE));
+        assert_eq!(
+            record.validate(),
+            Err(RecordError::UnexpectedAmount {
+                kind: TransactionKind::Chargeback,
+                amount: Decimal::ONE,
+            })
+        );
+    }
+
+    #[test]
+    fn validate_negative_amount() {
+        let amount = Decimal::new(-15, 1);
+        let record = Record::new(TransactionKind::Fee, 1, 7, Some(amount));
+        assert_eq!(
+            record.validate(),
+            Err(RecordError::NegativeAmount {
+                kind: TransactionKind::Fee,
+                amount,
+            })
+        );
+    }
+
+    #[test]
+    fn validate_excess_precision() {
+        let amount = Decimal::new(12345, 5);
+        let record = Record::new(TransactionKind::Deposit, 1, 7, Some(amount));
+        assert_eq!(
+            record.validate(),
+            Err(RecordError::ExcessPrecision { amount, scale: 4 })
+        );
+
+        // Trailing zeros are not significant.
+        let record = Record::new(TransactionKind::Deposit, 1, 7, Some(Decimal::new(10, 5)));
+        assert_eq!(record.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_ids_out_of_range() {
+        let deserialize = |csv: &str| {
+            csv::Reader::from_reader(csv.as_bytes())
+                .deserialize::<Record>()
+                .next()
+                .unwrap()
+        };
+
+        assert!(deserialize("type,client,tx,amount\ndeposit,65535,4294967295,1\n").is_ok());
+        assert!(deserialize("type,client,tx,amount\ndeposit,65536,1,1\n").is_err());
+        assert!(deserialize("type,client,tx,amount\ndeposit,1,4294967296,1\n").is_err());
+    }
+
+    #[test]
+    fn normalize_amount_prefixes() {
+        assert_eq!(normalize_amount("+100.00", None), "100.00");
+        assert_eq!(normalize_amount("$100.00", Some('$')), "100.00");
+        assert_eq!(normalize_amount("+$100.00", Some('$')), "100.00");
+        assert_eq!(normalize_amount("-$100.00", Some('$')), "-100.00");
+        assert_eq!(normalize_amount("-100.00", Some('$')), "-100.00");
+        assert_eq!(normalize_amount("$100.00", None), "$100.00");
+        assert_eq!(normalize_amount("€100.00", Some('€')), "100.00");
+        assert_eq!(normalize_amount("\"100.00\"", None), "100.00");
+        assert_eq!(normalize_amount("\" -$1.0 \"", Some('$')), "-1.0");
+        assert_eq!(normalize_amount("\"100.00", None), "\"100.00");
+    }
+
+    #[test]
+    fn negative_deposit() {
+        let amount = Decimal::NEGATIVE_ONE;
+        let result =
+            Transaction::<Processing>::try_from(received(TransactionKind::Deposit, amount));
+        assert_eq!(
+            result.unwrap_err(),
+            KoncordError::NegativeAmount { tx: 7, amount }
+        );
+    }
+
+    #[test]
+    fn negative_withdrawal() {
+        let amount = Decimal::new(-15, 1);
+        let result =
+            Transaction::<Processing>::try_from(received(TransactionKind::Withdrawal, amount));
+        assert_eq!(
+            result.unwrap_err(),
+            KoncordError::NegativeAmount { tx: 7, amount }
+        );
+    }
+
+    #[test]
+    fn negative_dispute() {
+        let amount = Decimal::NEGATIVE_ONE;
+        let mut dispute_lookup =
+            Transaction::<DisputeLookup>::try_from(received(TransactionKind::Dispute, amount))
+                .unwrap();
+        dispute_lookup.set_amount(Some(amount));
+        assert_eq!(
+            Transaction::<Processing>::try_from(dispute_lookup).unwrap_err(),
+            KoncordError::NegativeAmount { tx: 7, amount }
+        );
+    }
+
+    #[test]
+    fn negative_resolve() {
+        let amount = Decimal::NEGATIVE_ONE;
+        assert_eq!(
+            Transaction::<Resolved>::try_from(received(TransactionKind::Resolve, amount))
+                .unwrap_err(),
+            KoncordError::NegativeAmount { tx: 7, amount }
+        );
+    }
+
+    #[test]
+    fn positive_deposit() {
+        let amount = Decimal::ONE;
+        let processing =
+            Transaction::<Processing>::try_from(received(TransactionKind::Deposit, amount))
+                .unwrap();
+        assert_eq!(processing.amount(), amount);
+    }
+
+    #[test]
+    fn process_completed() {
+        let mut client = crate::client::Client::new(1);
+        let processing =
+            Transaction::<Processing>::try_from(received(TransactionKind::Deposit, Decimal::ONE))
+                .unwrap();
+
+        let completed = processing.process(client.get_mut()).unwrap();
+        assert_eq!(completed.result(), OpResult::Applied);
+    }
+
+    #[test]
+    fn process_failed() {
+        let mut client = crate::client::Client::new(1);
+        let account = client.get_mut();
+        account.deposit(Decimal::ONE);
+        account.dispute(1, Decimal::ONE);
+        account.chargeback(1, Decimal::ONE);
+        let processing =
+            Transaction::<Processing>::try_from(received(TransactionKind::Deposit, Decimal::ONE))
+                .unwrap();
+
+        let failed = processing.process(client.get_mut()).unwrap_err();
+        assert_eq!(
+            failed.state,
+            Failed {
+                reason: FailureReason::AccountLocked
+            }
+        );
+        assert_eq!(OpResult::from(failed), OpResult::SkippedLocked);
+    }
+
+    #[test]
+    fn corrected_referenced() {
+        let amount = Decimal::NEGATIVE_ONE;
+        let correction =
+            Transaction::<Corrected>::try_from(received(TransactionKind::Correction, amount))
+                .unwrap();
+        assert!(Transaction::<Processing>::try_from(correction.clone()).is_err());
+
+        let mut correction = correction;
+        correction.set_referenced();
+        let processing = Transaction::<Processing>::try_from(correction).unwrap();
+        assert_eq!(processing.amount(), amount);
+    }
+
+    #[test]
+    fn dispute_lookup_amount() {
+        let amount = Decimal::ONE;
+        let mut dispute_lookup =
+            Transaction::<DisputeLookup>::try_from(received(TransactionKind::Dispute, amount))
+                .unwrap();
+        dispute_lookup.set_amount(Some(amount));
+        let processing = Transaction::<Processing>::try_from(dispute_lookup).unwrap();
+        assert_eq!(processing.amount(), amount);
+    }
+
+    #[test]
+    fn record_round_trip_csv() {
+        let records = vec![
+            Record {
+                kind: TransactionKind::Deposit,
+                client: 1,
+                tx: 1,
+                amount: Some(Decimal::new(15, 1)),
+                description: None,
+                timestamp: None,
+            },
+            Record {
+                kind: TransactionKind::Deposit,
+                client: 1,
+                tx: 2,
+                amount: Some(Decimal::new(2, 0)),
+                description: Some("refund".to_string()),
+                timestamp: Some(1_660_000_000),
+            },
+            Record {
+                kind: TransactionKind::Dispute,
+                client: 1,
+                tx: 1,
+                amount: None,
+                description: None,
+                timestamp: Some(1_660_000_060),
+            },
+        ];
+
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        for record in &records {
+            wtr.serialize(record).unwrap();
+        }
+        let csv = String::from_utf8(wtr.into_inner().unwrap()).unwrap();
+        assert_eq!(
+            csv,
+            "type,client,tx,amount,description,timestamp\n\
+             deposit,1,1,1.5,,\n\
+             deposit,1,2,2,refund,1660000000\n\
+             dispute,1,1,,,1660000060\n"
+        );
+
+        let mut rdr = csv::Reader::from_reader(csv.as_bytes());
+        let parsed: Vec<Record> = rdr.deserialize().map(Result::unwrap).collect();
+        assert_eq!(parsed, records);
+    }
+
+    #[test]
+    fn record_round_trip_json() {
+        let record = Record {
+            kind: TransactionKind::Chargeback,
+            client: u16::MAX,
+            tx: u32::MAX,
+            amount: Some(Decimal::new(1, 4)),
+            description: None,
+            timestamp: None,
+        };
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"chargeback","client":65535,"tx":4294967295,"amount":"0.0001","description":null,"timestamp":null}"#
+        );
+        assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+    }
 }
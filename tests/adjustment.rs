@@ -0,0 +1,24 @@
+use koncord::Engine;
+use rust_decimal::Decimal;
+
+const ADJUSTMENT: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+adjustment, 1,      2,  2.5
+adjustment, 1,      3,  -3.0
+withdrawal, 1,      4,  1.0
+";
+
+#[test]
+fn adjustment() {
+    let transaction_records = koncord::reader_builder().from_reader(ADJUSTMENT.as_bytes());
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    // The negative adjustment leaves too little for the withdrawal.
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::new(5, 1));
+    assert_eq!(account.total(), Decimal::new(5, 1));
+    assert_eq!(engine.stats().applied, 3);
+    assert_eq!(engine.stats().skipped, 1);
+}
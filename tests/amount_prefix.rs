@@ -0,0 +1,57 @@
+use rust_decimal::Decimal;
+
+use koncord::{Engine, KoncordError};
+
+const PREFIXED: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  +100.00
+deposit,    2,      2,  $100.00
+deposit,    3,      3,  +$0.50
+";
+
+#[test]
+fn amount_prefix() {
+    let mut engine = Engine::new().currency_symbol('$');
+    engine
+        .process(koncord::reader_builder().from_reader(PREFIXED.as_bytes()))
+        .unwrap();
+
+    let available = |id| engine.client(id).unwrap().get().available();
+    assert_eq!(available(1), Decimal::new(100, 0));
+    assert_eq!(available(2), Decimal::new(100, 0));
+    assert_eq!(available(3), Decimal::new(5, 1));
+}
+
+#[test]
+fn amount_prefix_negative() {
+    let records = "type,client,tx,amount\nwithdrawal,1,1,-$1.00\n";
+
+    let mut engine = Engine::new().currency_symbol('$');
+    let err = engine
+        .process(koncord::reader_builder().from_reader(records.as_bytes()))
+        .unwrap_err();
+
+    assert_eq!(
+        err.downcast_ref::<KoncordError>(),
+        Some(&KoncordError::NegativeAmount {
+            tx: 1,
+            amount: Decimal::new(-100, 2),
+        })
+    );
+}
+
+#[test]
+fn amount_prefix_unset() {
+    let mut engine = Engine::new();
+    let err = engine
+        .process(koncord::reader_builder().from_reader(PREFIXED.as_bytes()))
+        .unwrap_err();
+
+    assert_eq!(
+        err.downcast_ref::<KoncordError>(),
+        Some(&KoncordError::InvalidAmount {
+            line: 3,
+            value: "$100.00".to_string(),
+        })
+    );
+}
@@ -0,0 +1,29 @@
+use koncord::{Record, TransactionKind};
+use rust_decimal::Decimal;
+
+#[test]
+fn run_async() {
+    let records = vec![
+        Record::new(TransactionKind::Deposit, 1, 1, Some(Decimal::new(15, 1))),
+        Record::new(TransactionKind::Deposit, 2, 2, Some(Decimal::TWO)),
+        Record::new(TransactionKind::Withdrawal, 1, 3, Some(Decimal::ONE)),
+        Record::new(TransactionKind::Dispute, 2, 2, None),
+        Record::new(TransactionKind::Deposit, 1, 4, Some(Decimal::ONE)),
+        Record::new(TransactionKind::Dispute, 1, 4, None),
+        Record::new(TransactionKind::Chargeback, 1, 4, None),
+    ];
+
+    let clients =
+        futures::executor::block_on(koncord::run_async(futures::stream::iter(records))).unwrap();
+
+    let account = clients[&1].get();
+    assert_eq!(account.available(), Decimal::new(5, 1));
+    assert_eq!(account.held(), Decimal::ZERO);
+    assert_eq!(account.total(), Decimal::new(5, 1));
+    assert!(account.is_locked());
+
+    let account = clients[&2].get();
+    assert_eq!(account.available(), Decimal::ZERO);
+    assert_eq!(account.held(), Decimal::TWO);
+    assert_eq!(account.total(), Decimal::TWO);
+}
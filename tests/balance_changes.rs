@@ -0,0 +1,46 @@
+use rust_decimal::Decimal;
+
+use koncord::{Engine, TransactionKind};
+
+const RECORDS: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  2.0
+withdrawal, 1,      2,  5.0
+dispute,    1,      1,
+";
+
+#[test]
+fn balance_changes() {
+    let transaction_records = koncord::reader_builder().from_reader(RECORDS.as_bytes());
+
+    let mut engine = Engine::new().balance_changes_enabled(true);
+    engine.process(transaction_records).unwrap();
+
+    // The refused withdrawal changes nothing so is not kept.
+    let changes = engine.balance_changes();
+    assert_eq!(changes.len(), 2);
+
+    let deposit = &changes[0];
+    assert_eq!((deposit.client, deposit.tx), (1, 1));
+    assert_eq!(deposit.kind, TransactionKind::Deposit);
+    assert!(deposit.before.is_zero());
+    assert_eq!(deposit.after.available(), Decimal::new(2, 0));
+    assert_eq!(deposit.after.held(), Decimal::ZERO);
+    assert_eq!(deposit.after.total(), Decimal::new(2, 0));
+
+    let dispute = &changes[1];
+    assert_eq!(dispute.kind, TransactionKind::Dispute);
+    assert_eq!(dispute.before, deposit.after);
+    assert_eq!(dispute.after.available(), Decimal::ZERO);
+    assert_eq!(dispute.after.held(), Decimal::new(2, 0));
+}
+
+#[test]
+fn balance_changes_disabled() {
+    let transaction_records = koncord::reader_builder().from_reader(RECORDS.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    assert!(engine.balance_changes().is_empty());
+}
@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use koncord::client::Client;
+
+const BOM_CRLF_EXPECTED: &str = "\
+client,available,held,total,locked
+1,1.5,0.0000,1.5,false
+2,2,0.0000,2,false
+";
+
+#[test]
+fn bom_crlf() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/bom_crlf.csv");
+
+    let mut clients: HashMap<u16, Client> = HashMap::with_capacity(usize::from(u16::MAX));
+
+    let mut transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    assert_eq!(
+        transaction_records.headers().unwrap(),
+        vec!["type", "client", "tx", "amount"]
+    );
+
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    koncord::run(
+        &mut clients,
+        transaction_records,
+        records_path.to_str().unwrap(),
+    )
+    .unwrap();
+
+    let mut wtr = csv::Writer::from_writer(vec![]);
+    let mut clients: Vec<&Client> = clients.values().collect();
+    clients.sort();
+    for client in clients {
+        wtr.serialize(client).unwrap();
+    }
+    wtr.flush().unwrap();
+    assert_eq!(
+        String::from_utf8(wtr.into_inner().unwrap()).unwrap(),
+        BOM_CRLF_EXPECTED
+    );
+}
@@ -0,0 +1,55 @@
+use koncord::client::ChargebackPolicy;
+use koncord::Engine;
+
+const CHARGEBACK: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  5.0
+deposit,    1,      2,  2.0
+dispute,    1,      2,
+chargeback, 1,      2,
+deposit,    1,      3,  1.0
+withdrawal, 1,      4,  3.0
+deposit,    2,      5,  1.0
+";
+
+#[test]
+fn chargeback_freezes() {
+    let transaction_records = koncord::reader_builder().from_reader(CHARGEBACK.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    let mut output = vec![];
+    engine.write(&mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "\
+client,available,held,total,locked
+1,5,0,5,true
+2,1,0.0000,1,false
+"
+    );
+}
+
+#[test]
+fn chargeback_flags() {
+    let transaction_records = koncord::reader_builder().from_reader(CHARGEBACK.as_bytes());
+
+    let mut engine = Engine::new().chargeback_policy(ChargebackPolicy::Flag);
+    engine.process(transaction_records).unwrap();
+
+    let client = engine.client(1).unwrap();
+    assert!(client.get().is_flagged());
+    assert!(!client.get().is_locked());
+
+    let mut output = vec![];
+    engine.write(&mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "\
+client,available,held,total,locked,flagged
+1,3,0,3,false,true
+2,1,0.0000,1,false,false
+"
+    );
+}
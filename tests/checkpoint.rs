@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+const CHECKPOINT_EXPECTED: &str = "\
+client,available,held,total,locked
+1,1,0.0000,1,false
+2,2,0.0000,2,false
+client,available,held,total,locked
+1,1.5,0.0000,1.5,false
+2,2,0.0000,2,false
+";
+
+#[test]
+fn checkpoint_every_two() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/toy/base.csv");
+
+    let mut checkpoints = vec![];
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    let clients = koncord::run_with_checkpoint(transaction_records, &mut checkpoints, 2).unwrap();
+
+    // Five records are checkpointed after the second and fourth.
+    assert_eq!(String::from_utf8(checkpoints).unwrap(), CHECKPOINT_EXPECTED);
+    assert_eq!(clients.len(), 2);
+}
+
+#[test]
+fn checkpoint_never() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/toy/base.csv");
+
+    let mut checkpoints = vec![];
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    koncord::run_with_checkpoint(transaction_records, &mut checkpoints, 0).unwrap();
+
+    assert!(checkpoints.is_empty());
+}
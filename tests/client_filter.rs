@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use koncord::client::Client;
+
+const FILTER_EXPECTED: &str = "\
+client,available,held,total,locked
+1,1.5,0.0000,1.5,false
+";
+
+#[test]
+#[allow(unused_must_use)]
+fn client_filter_drop() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/toy/base.csv");
+
+    let mut clients: HashMap<u16, Client> = HashMap::with_capacity(usize::from(u16::MAX));
+
+    let transaction_records = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_path(&records_path)
+        .unwrap();
+
+    koncord::run_with_client_filter(
+        &mut clients,
+        transaction_records,
+        records_path.to_str().unwrap(),
+        |id| if id == 2 { None } else { Some(id) },
+    );
+
+    let mut wtr = csv::Writer::from_writer(vec![]);
+    let mut clients: Vec<&Client> = clients.values().collect();
+    clients.sort();
+    for client in clients {
+        wtr.serialize(client);
+    }
+    wtr.flush();
+    assert_eq!(
+        String::from_utf8(wtr.into_inner().unwrap()).unwrap(),
+        FILTER_EXPECTED
+    );
+}
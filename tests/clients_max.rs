@@ -1,14 +1,12 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-use koncord;
 use koncord::client::Client;
 
 #[test]
 #[allow(unused_must_use)]
 fn clients_max() {
-    let mut clients: HashMap<u16, Client> =
-        HashMap::with_capacity(usize::try_from(u16::MAX).unwrap());
+    let mut clients: HashMap<u16, Client> = HashMap::with_capacity(usize::from(u16::MAX));
 
     let mut records = String::from("type,       client, tx, amount\n");
     for id in 0..=u16::MAX {
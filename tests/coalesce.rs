@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use koncord::Engine;
+
+// Runs of deposits to a client, one of which is disputed and charged back.
+const DEPOSITS: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+deposit,    1,      2,  2.0
+deposit,    1,      3,  3.0
+deposit,    2,      4,  4.0
+deposit,    2,      5,  5.0
+withdrawal, 1,      6,  0.5
+deposit,    1,      7,  1.5
+deposit,    1,      8,  0.0
+dispute,    1,      2,
+chargeback, 1,      2,
+deposit,    1,      10, 1.0
+deposit,    2,      11, 1.0
+dispute,    2,      5,
+";
+
+fn process(mut engine: Engine, records: &[u8]) -> (String, koncord::RunStats) {
+    engine
+        .process(koncord::reader_builder().from_reader(records))
+        .unwrap();
+
+    let mut output = vec![];
+    engine.write(&mut output).unwrap();
+    (String::from_utf8(output).unwrap(), engine.stats().clone())
+}
+
+#[test]
+fn coalesce_deposits() {
+    let (expected, expected_stats) = process(Engine::new(), DEPOSITS.as_bytes());
+    let (output, stats) = process(Engine::new().coalesce_deposits(true), DEPOSITS.as_bytes());
+
+    assert_eq!(output, expected);
+    assert_eq!(stats, expected_stats);
+}
+
+#[test]
+fn coalesce_deposits_complex() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/complex.csv");
+    let records = std::fs::read(&records_path).unwrap();
+
+    let (expected, _) = process(Engine::new(), &records);
+    let (output, _) = process(Engine::new().coalesce_deposits(true), &records);
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn coalesce_deposits_overflow() {
+    // Deposits fitting on their own until the balance overflows. The net of
+    // client 1's deposits overflows `Decimal`, that of client 2's only
+    // overflows its balance.
+    let large = "7000000000000000000000000000.0";
+    let mut records = String::from("type,client,tx,amount\n");
+    for tx in 1..=12 {
+        records.push_str(&format!("deposit,1,{tx},{large}\n"));
+    }
+    records.push_str("deposit,1,13,1.0\n");
+    for tx in 14..=19 {
+        records.push_str(&format!("deposit,2,{tx},{large}\n"));
+    }
+    records.push_str("withdrawal,2,20,1.0\n");
+    for tx in 21..=26 {
+        records.push_str(&format!("deposit,2,{tx},{large}\n"));
+    }
+
+    let (expected, expected_stats) = process(Engine::new(), records.as_bytes());
+    let (output, stats) = process(Engine::new().coalesce_deposits(true), records.as_bytes());
+
+    assert_eq!(output, expected);
+    assert_eq!(stats, expected_stats);
+    assert_eq!(stats.skipped, 2);
+}
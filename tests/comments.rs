@@ -0,0 +1,24 @@
+use std::path::PathBuf;
+
+use koncord::Engine;
+
+const COMMENTS_EXPECTED: &str = "\
+client,available,held,total,locked
+1,1.5,0.0000,1.5,false
+2,2,0.0000,2,false
+";
+
+#[test]
+fn comments_and_blank_lines() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/comments.csv");
+
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+    assert_eq!(engine.stats().records, 5);
+
+    let mut written = vec![];
+    engine.write(&mut written).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), COMMENTS_EXPECTED);
+}
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::process::Command;
 
-use koncord;
 use koncord::client::Client;
 
 const COMPLEX_EXPECTED: &str = "\
@@ -19,8 +19,7 @@ fn complex() {
     let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     records_path.push("tests/data/complex.csv");
 
-    let mut clients: HashMap<u16, Client> =
-        HashMap::with_capacity(usize::try_from(u16::MAX).unwrap());
+    let mut clients: HashMap<u16, Client> = HashMap::with_capacity(usize::from(u16::MAX));
 
     let transaction_records = csv::ReaderBuilder::new()
         .trim(csv::Trim::All)
@@ -46,3 +45,19 @@ fn complex() {
         COMPLEX_EXPECTED
     );
 }
+
+// The binary processes a file as `run` does, clients are written in Client ID
+// order.
+#[test]
+fn complex_cli() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/complex.csv");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_koncord"))
+        .arg(&records_path)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), COMPLEX_EXPECTED);
+}
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use koncord::Engine;
+
+#[derive(Debug, Deserialize)]
+struct Row {
+    client: u16,
+    total: Decimal,
+    computed_total: Decimal,
+}
+
+#[test]
+fn computed_total_matches_total() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/complex.csv");
+
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    let mut engine = Engine::new().computed_total(true);
+    engine.process(transaction_records).unwrap();
+
+    let mut output = vec![];
+    engine.write(&mut output).unwrap();
+
+    let mut rdr = csv::Reader::from_reader(output.as_slice());
+    let rows: Vec<Row> = rdr.deserialize().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), engine.clients().count());
+    for row in rows {
+        assert_eq!(row.computed_total, row.total, "client {}", row.client);
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use rust_decimal::Decimal;
+
+use koncord::client::Client;
+use koncord::Engine;
+
+fn records_path() -> PathBuf {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/correction.csv");
+    records_path
+}
+
+// Client 1 corrects deposit 1 twice, its correction of missing transaction 9
+// is refused as is the correction by client 2 of another client's deposit.
+fn assert_corrected(clients: &HashMap<u16, Client>) {
+    let account = clients[&1].get();
+    assert_eq!(account.available(), Decimal::new(875, 2));
+    assert_eq!(account.total(), Decimal::new(875, 2));
+
+    let account = clients[&2].get();
+    assert_eq!(account.available(), Decimal::new(4, 0));
+}
+
+#[test]
+fn correction() {
+    let transaction_records = koncord::reader_builder().from_path(records_path()).unwrap();
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    assert_eq!(engine.stats().orphan_corrections, 2);
+    assert_eq!(engine.stats().rejected(), 2);
+    assert_corrected(&engine.into_clients());
+}
+
+#[test]
+fn correction_run() {
+    let records_path = records_path();
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    koncord::run(
+        &mut clients,
+        transaction_records,
+        records_path.to_str().unwrap(),
+    )
+    .unwrap();
+
+    assert_corrected(&clients);
+}
@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use koncord::client::Client;
+
+const DIAGNOSTICS: &str = "\
+type,     client, tx, amount
+deposit,  1,      1,  2.0
+refund,   1,      2,  1.0
+withdrawal, 1,    3,  0.5
+";
+
+#[test]
+fn diagnostics_captured() {
+    let transaction_records = koncord::reader_builder().from_reader(DIAGNOSTICS.as_bytes());
+
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    let mut diagnostics: Vec<u8> = Vec::new();
+    koncord::run_with_diagnostics(&mut clients, transaction_records, "", &mut diagnostics).unwrap();
+
+    assert_eq!(clients[&1].get().total(), Decimal::new(15, 1));
+    let diagnostics = String::from_utf8(diagnostics).unwrap();
+    let lines: Vec<&str> = diagnostics.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("record Record { kind: Deposit, client: 1, tx: 1,"));
+    assert!(lines[1].starts_with("skipped Record { kind: Unknown(\"refund\"), client: 1, tx: 2,"));
+    assert!(lines[2].starts_with("record Record { kind: Withdrawal, client: 1, tx: 3,"));
+}
@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn directory() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/daily");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_koncord"))
+        .arg(&records_path)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines: Vec<&str> = stdout.lines().collect();
+    lines.sort();
+    assert_eq!(
+        lines,
+        [
+            "1,0,5,5,false",
+            "2,2,0.0000,2,false",
+            "client,available,held,total,locked",
+        ]
+    );
+}
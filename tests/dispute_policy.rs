@@ -0,0 +1,64 @@
+use rust_decimal::Decimal;
+
+use koncord::client::DisputePolicy;
+use koncord::Engine;
+
+const WITHDRAWN: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  100.0
+deposit,    1,      2,  1.0
+withdrawal, 1,      3,  100.0
+dispute,    1,      1,
+resolve,    1,      1,
+";
+
+fn process(policy: DisputePolicy) -> Engine {
+    let transaction_records = koncord::reader_builder().from_reader(WITHDRAWN.as_bytes());
+
+    let mut engine = Engine::new().dispute_policy(policy);
+    engine.process(transaction_records).unwrap();
+    engine
+}
+
+#[test]
+fn dispute_policy_overdraft() {
+    let engine = process(DisputePolicy::Overdraft);
+    let account = engine.client(1).unwrap().get();
+
+    assert_eq!(account.available(), Decimal::ONE);
+    assert_eq!(account.held(), Decimal::ZERO);
+    assert_eq!(engine.stats().applied, 5);
+}
+
+#[test]
+fn dispute_policy_refuse() {
+    let engine = process(DisputePolicy::Refuse);
+    let account = engine.client(1).unwrap().get();
+
+    assert_eq!(account.available(), Decimal::ONE);
+    assert_eq!(account.held(), Decimal::ZERO);
+    assert_eq!(engine.stats().applied, 3);
+    assert_eq!(engine.stats().skipped, 1);
+    assert_eq!(engine.stats().disputes_refused, 1);
+}
+
+#[test]
+fn dispute_policy_refuse_partially_withdrawn() {
+    let records = "\
+type,       client, tx, amount
+deposit,    1,      1,  100.0
+withdrawal, 1,      2,  50.0
+dispute,    1,      1,
+";
+
+    let mut engine = Engine::new().dispute_policy(DisputePolicy::Refuse);
+    engine
+        .process(koncord::reader_builder().from_reader(records.as_bytes()))
+        .unwrap();
+
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::new(50, 0));
+    assert_eq!(account.held(), Decimal::ZERO);
+    assert_eq!(engine.stats().disputes_refused, 1);
+    assert!(engine.open_disputes().is_empty());
+}
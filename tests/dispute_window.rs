@@ -0,0 +1,33 @@
+use rust_decimal::Decimal;
+
+use koncord::Engine;
+
+const DISPUTE_WINDOW: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+deposit,    1,      2,  2.0
+deposit,    1,      3,  4.0
+dispute,    1,      1,
+dispute,    1,      3,
+";
+
+#[test]
+fn dispute_window() {
+    let transaction_records = koncord::reader_builder().from_reader(DISPUTE_WINDOW.as_bytes());
+
+    let mut engine = Engine::new().max_dispute_age(2);
+    engine.process(transaction_records).unwrap();
+
+    // The dispute of tx 1 is three records old and refused while the dispute
+    // of tx 3 is exactly two records old and holds its funds.
+    let account = engine
+        .clients()
+        .find(|client| client.id() == 1)
+        .unwrap()
+        .get();
+    assert_eq!(account.available(), Decimal::new(30, 1));
+    assert_eq!(account.held(), Decimal::new(40, 1));
+    assert_eq!(account.total(), Decimal::new(70, 1));
+    assert_eq!(engine.stats().records, 5);
+    assert_eq!(engine.stats().disputes_expired, 1);
+}
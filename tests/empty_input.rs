@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+const HEADER: &str = "client,available,held,total,locked\n";
+
+fn koncord(args: &[&str], file: &str) -> std::process::Output {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data");
+    records_path.push(file);
+
+    Command::new(env!("CARGO_BIN_EXE_koncord"))
+        .args(args)
+        .arg(&records_path)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn empty_input() {
+    let output = koncord(&[], "empty.csv");
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), HEADER);
+}
+
+#[test]
+fn header_only_input() {
+    let output = koncord(&[], "header_only.csv");
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), HEADER);
+}
+
+#[test]
+fn empty_input_omit_header() {
+    for file in ["empty.csv", "header_only.csv"] {
+        let output = koncord(&["--omit-empty-header"], file);
+
+        assert!(output.status.success());
+        assert!(output.stdout.is_empty());
+    }
+}
+
+#[test]
+fn empty_input_computed_total() {
+    let output = koncord(&["--computed-total"], "header_only.csv");
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "client,available,held,total,locked,computed_total\n"
+    );
+}
+
+#[test]
+fn empty_input_run() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/empty.csv");
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+
+    let mut clients = std::collections::HashMap::new();
+    koncord::run(
+        &mut clients,
+        transaction_records,
+        records_path.to_str().unwrap(),
+    )
+    .unwrap();
+
+    assert!(clients.is_empty());
+}
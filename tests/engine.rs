@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use koncord::Engine;
+use rust_decimal::Decimal;
+
+fn complex() -> Engine {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/complex.csv");
+
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+    engine
+}
+
+#[test]
+fn engine_client_lookup() {
+    let engine = complex();
+
+    assert!(engine.contains_client(1000));
+    assert_eq!(engine.client(1000).unwrap().id(), 1000);
+    assert_eq!(
+        engine.client(1000).unwrap().get().total(),
+        Decimal::new(500, 0)
+    );
+
+    assert!(!engine.contains_client(3));
+    assert!(engine.client(3).is_none());
+}
+
+#[test]
+fn engine_minor_units() {
+    let transaction_records = koncord::reader_builder()
+        .from_reader("type, client, tx, amount\ndeposit, 1, 1, 1.5\n".as_bytes());
+    let mut engine = Engine::new().minor_units(2);
+    engine.process(transaction_records).unwrap();
+
+    let mut written = vec![];
+    engine.write(&mut written).unwrap();
+    assert_eq!(
+        String::from_utf8(written).unwrap(),
+        "client,available,held,total,locked\n1,150,0,150,false\n"
+    );
+}
+
+#[test]
+fn engine_accounts_sorted() {
+    let engine = complex();
+
+    let ids: Vec<u16> = engine.accounts().map(|(id, _)| id).collect();
+    assert_eq!(ids, [1, 2, 999, 1000, 1001]);
+
+    let balances: Vec<(Decimal, Decimal, Decimal, bool)> = engine
+        .accounts()
+        .map(|(_, account)| {
+            (
+                account.available(),
+                account.held(),
+                account.total(),
+                account.is_locked(),
+            )
+        })
+        .collect();
+    let zero = Decimal::ZERO;
+    let one_half = Decimal::new(15, 1);
+    let two = Decimal::new(2, 0);
+    let five_hundred = Decimal::new(500, 0);
+    assert_eq!(
+        balances,
+        [
+            (one_half, zero, one_half, false),
+            (two, zero, two, false),
+            (zero, zero, zero, false),
+            (five_hundred, zero, five_hundred, false),
+            (zero, zero, zero, false),
+        ]
+    );
+}
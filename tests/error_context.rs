@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use koncord::client::Client;
+use koncord::{KoncordError, RecordSource};
+
+#[test]
+fn csv_error() {
+    let records = "type,client,tx,amount\ndeposit,1,1,1.0\ndeposit,one,2,1.0\n";
+    let mut transaction_records = koncord::reader_builder().from_reader(records.as_bytes());
+    transaction_records.next_record().unwrap();
+
+    let err = transaction_records.next_record().unwrap_err();
+
+    match &err {
+        KoncordError::Csv { context, line, .. } => {
+            assert_eq!(context, "reading input");
+            assert_eq!(*line, Some(3));
+        }
+        err => panic!("unexpected error {err:?}"),
+    }
+    assert_eq!(
+        err.to_string(),
+        "CSV error reading input: CSV deserialize error: record 2 \
+         (line: 3, byte: 38): field 1: invalid digit found in string"
+    );
+}
+
+#[test]
+fn io_error_context() {
+    let records = "type,client,tx,amount\ndispute,1,1,\n";
+    let transaction_records = koncord::reader_builder().from_reader(records.as_bytes());
+
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    let err = koncord::run(&mut clients, transaction_records, "tests/data/absent.csv")
+        .unwrap_err()
+        .downcast::<KoncordError>()
+        .unwrap();
+
+    match &*err {
+        KoncordError::Io { context, .. } => {
+            assert_eq!(context, "reading dispute lookup records");
+        }
+        err => panic!("unexpected error {err:?}"),
+    }
+    assert!(err
+        .to_string()
+        .starts_with("IO error reading dispute lookup records: "));
+}
+
+#[test]
+fn context() {
+    let err = KoncordError::from(std::io::Error::other("disk on fire")).context("writing output");
+    assert_eq!(
+        err,
+        KoncordError::Io {
+            context: "writing output".to_string(),
+            message: "disk on fire".to_string(),
+        }
+    );
+
+    let err = KoncordError::MissingTx { line: 1 }.context("writing output");
+    assert_eq!(err, KoncordError::MissingTx { line: 1 });
+}
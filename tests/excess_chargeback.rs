@@ -0,0 +1,52 @@
+use rust_decimal::Decimal;
+
+use koncord::client::ExcessChargebackPolicy;
+use koncord::Engine;
+
+const EXCESS_CHARGEBACK: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  100.0
+withdrawal, 1,      2,  80.0
+dispute,    1,      1,
+chargeback, 1,      1,
+";
+
+fn process(policy: ExcessChargebackPolicy) -> Engine {
+    let transaction_records = koncord::reader_builder().from_reader(EXCESS_CHARGEBACK.as_bytes());
+
+    let mut engine = Engine::new().excess_chargeback_policy(policy);
+    engine.process(transaction_records).unwrap();
+    assert_eq!(engine.stats().excess_chargebacks, 1);
+    engine
+}
+
+#[test]
+fn excess_chargeback_allowed() {
+    let engine = process(ExcessChargebackPolicy::Allow);
+    let account = engine.client(1).unwrap().get();
+
+    assert_eq!(account.total(), Decimal::new(-80, 0));
+    assert!(account.is_locked());
+}
+
+#[test]
+fn excess_chargeback_clamped() {
+    let engine = process(ExcessChargebackPolicy::Clamp);
+    let account = engine.client(1).unwrap().get();
+
+    assert_eq!(account.total(), Decimal::ZERO);
+    assert_eq!(account.available() + account.held(), account.total());
+    assert!(account.is_locked());
+    assert_eq!(engine.stats().applied, 4);
+}
+
+#[test]
+fn excess_chargeback_refused() {
+    let engine = process(ExcessChargebackPolicy::Refuse);
+    let account = engine.client(1).unwrap().get();
+
+    assert_eq!(account.held(), Decimal::new(100, 0));
+    assert_eq!(account.total(), Decimal::new(20, 0));
+    assert!(!account.is_locked());
+    assert_eq!(engine.stats().skipped, 1);
+}
@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+const EXPECTED: &str = "\
+client,available,held,total,locked
+1,1,0.0000,1,false
+";
+
+fn koncord(args: &[&str]) -> std::process::Output {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/insufficient_funds.csv");
+
+    Command::new(env!("CARGO_BIN_EXE_koncord"))
+        .args(args)
+        .arg(&records_path)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn fail_on_reject() {
+    let output = koncord(&["--fail-on-reject"]);
+
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), EXPECTED);
+}
+
+#[test]
+fn fail_on_reject_unset() {
+    let output = koncord(&[]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), EXPECTED);
+}
@@ -0,0 +1,25 @@
+use std::path::PathBuf;
+
+use koncord::Engine;
+
+// Results must not depend on the hasher, run with and without the
+// `fast-hash` feature.
+fn process(mut engine: Engine) -> String {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/100k_transactions.csv");
+
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    engine.process(transaction_records).unwrap();
+
+    let mut output = vec![];
+    engine.write(&mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn hashed_matches_ordered() {
+    assert_eq!(
+        process(Engine::new()),
+        process(Engine::new().ordered_clients(true))
+    );
+}
@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use koncord::client::Client;
+use koncord::Engine;
+
+// The deposit and withdrawal fast path must match the transaction state
+// machine used by `run`.
+//
+// The records include insufficient funds, withdrawals of held funds and
+// deposits to a locked account.
+#[test]
+fn fast_path_matches_state_machine() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/funds.csv");
+
+    let mut engine = Engine::new();
+    engine
+        .process(koncord::reader_builder().from_path(&records_path).unwrap())
+        .unwrap();
+
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    koncord::run(
+        &mut clients,
+        koncord::reader_builder().from_path(&records_path).unwrap(),
+        records_path.to_str().unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(engine.clients().count(), clients.len());
+    for client in engine.clients() {
+        assert!(client.balances_equal(&clients[&client.id()]));
+    }
+}
@@ -0,0 +1,37 @@
+use rust_decimal::Decimal;
+
+use koncord::client::FeePolicy;
+use koncord::Engine;
+
+const FEES: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+fee,        1,      2,  0.5
+fee,        1,      3,  2.0
+";
+
+fn process(mut engine: Engine) -> Engine {
+    engine
+        .process(koncord::reader_builder().from_reader(FEES.as_bytes()))
+        .unwrap();
+    engine
+}
+
+#[test]
+fn fee_overdraft() {
+    let engine = process(Engine::new().fee_policy(FeePolicy::Overdraft));
+
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::new(-15, 1));
+    assert_eq!(account.total(), Decimal::new(-15, 1));
+    assert_eq!(engine.stats().applied, 3);
+}
+
+#[test]
+fn fee_refused() {
+    let engine = process(Engine::new());
+
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::new(5, 1));
+    assert_eq!(engine.stats().skipped, 1);
+}
@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+const EXPECTED: &str = "\
+client,available,held,total,locked
+1,000000001.0000,000000000.5000,000000001.5000,false
+2,000000001.5000,000000000.5000,000000002.0000,false
+999,000000000.0000,000000000.0000,000000000.0000,false
+1000,000000500.0000,000000000.0000,000000500.0000,false
+1001,000000000.0000,000000000.0000,000000000.0000,false
+";
+
+#[test]
+fn fixed_width() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/complex.csv");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_koncord"))
+        .args(["--scale", "4", "--fixed-width", "14"])
+        .arg(&records_path)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), EXPECTED);
+}
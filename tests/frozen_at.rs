@@ -0,0 +1,21 @@
+use koncord::Engine;
+
+const RECORDS: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+deposit,    1,      2,  2.0
+dispute,    1,      2,
+chargeback, 1,      2,
+deposit,    2,      3,  1.0
+";
+
+#[test]
+fn frozen_at_chargeback() {
+    let mut engine = Engine::new();
+    engine
+        .process(koncord::reader_builder().from_reader(RECORDS.as_bytes()))
+        .unwrap();
+
+    assert_eq!(engine.client(1).unwrap().frozen_at(), Some(2));
+    assert_eq!(engine.client(2).unwrap().frozen_at(), None);
+}
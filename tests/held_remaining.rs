@@ -0,0 +1,79 @@
+use rust_decimal::Decimal;
+
+use koncord::{Engine, KoncordError};
+
+const RECORDS: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  2.0
+deposit,    2,      2,  3.0
+deposit,    2,      3,  1.5
+dispute,    1,      1,
+dispute,    2,      2,
+resolve,    2,      2,
+dispute,    2,      3,
+";
+
+#[test]
+fn held_remaining() {
+    let transaction_records = koncord::reader_builder().from_reader(RECORDS.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    // Disputes of transactions 1 and 3 are never closed.
+    assert_eq!(engine.stats().total_held_remaining, Decimal::new(35, 1));
+}
+
+#[test]
+fn held_remaining_fail() {
+    let transaction_records = koncord::reader_builder().from_reader(RECORDS.as_bytes());
+
+    let mut engine = Engine::new().fail_on_held_remaining(true);
+    let err = engine.process(transaction_records).unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<KoncordError>(),
+        Some(KoncordError::HeldRemaining { held }) if *held == Decimal::new(35, 1)
+    ));
+    // Balances are still processed for inspection.
+    assert_eq!(engine.client(1).unwrap().get().held(), Decimal::new(2, 0));
+}
+
+#[test]
+fn held_remaining_closed() {
+    let records = "\
+type,       client, tx, amount
+deposit,    1,      1,  2.0
+dispute,    1,      1,
+chargeback, 1,      1,
+";
+    let transaction_records = koncord::reader_builder().from_reader(records.as_bytes());
+
+    let mut engine = Engine::new().fail_on_held_remaining(true);
+    engine.process(transaction_records).unwrap();
+
+    assert!(engine.stats().total_held_remaining.is_zero());
+}
+
+#[test]
+fn held_remaining_saturates() {
+    // The funds held by each account are valid, their sum exceeds
+    // `Decimal::MAX`.
+    let mut records = String::from("type,client,tx,amount\n");
+    for client in 1..=12 {
+        records.push_str(&format!(
+            "deposit,{client},{client},7000000000000000000000000000.0\n\
+             dispute,{client},{client},\n"
+        ));
+    }
+    let transaction_records = koncord::reader_builder().from_reader(records.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    assert_eq!(engine.stats().total_held_remaining, Decimal::MAX);
+    assert_eq!(
+        engine.client(12).unwrap().get().held(),
+        "7000000000000000000000000000.0".parse::<Decimal>().unwrap()
+    );
+}
@@ -0,0 +1,54 @@
+use rust_decimal::Decimal;
+
+use koncord::Engine;
+
+const HOLDS: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  10.0
+hold,       1,      2,  4.0
+withdrawal, 1,      3,  8.0
+release,    1,      2,  1.5
+hold,       2,      4,  1.0
+";
+
+#[test]
+fn hold_release() {
+    let transaction_records = koncord::reader_builder().from_reader(HOLDS.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::new(75, 1));
+    assert_eq!(account.held(), Decimal::new(25, 1));
+    assert_eq!(account.total(), Decimal::TEN);
+
+    // The withdrawal exceeds the available funds once held, client 2 has no
+    // funds to hold.
+    assert_eq!(engine.stats().applied, 3);
+    assert_eq!(engine.stats().skipped, 2);
+}
+
+#[test]
+fn release_disputed() {
+    let records = "\
+type,       client, tx, amount
+deposit,    1,      1,  100.0
+dispute,    1,      1,
+release,    1,      1,  100.0
+chargeback, 1,      1,
+";
+    let transaction_records = koncord::reader_builder().from_reader(records.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    // The release cannot draw on the funds held by the dispute, which are
+    // charged back in full.
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::ZERO);
+    assert_eq!(account.held(), Decimal::ZERO);
+    assert_eq!(account.total(), Decimal::ZERO);
+    assert!(account.is_locked());
+    assert_eq!(engine.stats().skipped, 1);
+}
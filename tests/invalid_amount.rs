@@ -0,0 +1,140 @@
+use rust_decimal::Decimal;
+
+use koncord::{Engine, KoncordError, ReaderConfig, SchemaError};
+
+fn process(amount: &str) -> Result<Engine, Box<dyn std::error::Error>> {
+    let records =
+        format!("type, client, tx, amount\ndeposit, 1, 1, 1.0\ndeposit, 1, 2, {amount}\n");
+    let transaction_records = koncord::reader_builder().from_reader(records.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records)?;
+    Ok(engine)
+}
+
+fn assert_invalid(amount: &str) {
+    let err = process(amount).err().unwrap();
+    assert_eq!(
+        err.downcast_ref::<KoncordError>(),
+        Some(&KoncordError::InvalidAmount {
+            line: 3,
+            value: amount.to_string()
+        })
+    );
+}
+
+#[test]
+fn invalid_amount_inf() {
+    assert_invalid("inf");
+    assert_invalid("-Infinity");
+}
+
+#[test]
+fn invalid_amount_nan() {
+    assert_invalid("nan");
+    assert_invalid("NaN");
+}
+
+#[test]
+fn invalid_amount_exponent() {
+    assert_invalid("1e1000");
+    assert_invalid("1e5");
+}
+
+#[test]
+fn invalid_amount_overflow() {
+    assert_invalid("79228162514264337593543950336");
+}
+
+#[test]
+fn valid_amount() {
+    let engine = process("2.5").unwrap();
+    assert_eq!(engine.stats().applied, 2);
+}
+
+#[test]
+fn negative_amount() {
+    let err = process("-2.5").err().unwrap();
+    assert_eq!(
+        err.downcast_ref::<KoncordError>(),
+        Some(&KoncordError::NegativeAmount {
+            tx: 2,
+            amount: "-2.5".parse().unwrap()
+        })
+    );
+    assert_eq!(err.to_string(), "Negative amount -2.5 for transaction 2");
+}
+
+#[test]
+fn invalid_amount_too_large() {
+    let amount = "1".repeat(koncord::MAX_RECORD_SIZE);
+    let err = process(&amount).err().unwrap();
+    assert_eq!(
+        err.downcast_ref::<KoncordError>(),
+        Some(&KoncordError::RecordTooLarge { line: 3 })
+    );
+}
+
+#[test]
+fn invalid_amount_too_large_run() {
+    let records = format!(
+        "type, client, tx, amount\ndeposit, 1, 1, {}\n",
+        "9".repeat(1 << 20)
+    );
+    let transaction_records = koncord::reader_builder().from_reader(records.as_bytes());
+
+    let mut clients = std::collections::HashMap::new();
+    let err = koncord::run(&mut clients, transaction_records, "").unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<KoncordError>(),
+        Some(&KoncordError::RecordTooLarge { line: 2 })
+    );
+}
+
+// Records of an endless amount, the oversized record must fail as it is read
+// rather than be read whole.
+fn endless_amount() -> impl std::io::Read {
+    use std::io::Read;
+
+    b"type, client, tx, amount\ndeposit, 1, 1, 1.0\ndeposit, 1, 2, ".chain(std::io::repeat(b'9'))
+}
+
+#[test]
+fn invalid_amount_too_large_limited() {
+    let transaction_records = ReaderConfig::default().from_reader(endless_amount());
+
+    let mut engine = Engine::new();
+    let err = engine.process(transaction_records).unwrap_err();
+    assert_eq!(
+        KoncordError::from(err),
+        KoncordError::RecordTooLarge { line: 3 }
+    );
+    // Records before the oversized record are processed.
+    assert_eq!(engine.client(1).unwrap().get().total(), Decimal::ONE);
+}
+
+#[test]
+fn invalid_amount_too_large_validate() {
+    let transaction_records = ReaderConfig::default().from_reader(endless_amount());
+    assert_eq!(
+        koncord::run_validate(transaction_records),
+        vec![KoncordError::RecordTooLarge { line: 3 }]
+    );
+
+    let transaction_records = ReaderConfig::default().from_reader(endless_amount());
+    assert_eq!(
+        koncord::validate_schema(transaction_records),
+        Err(vec![SchemaError::RecordTooLarge { line: 3 }])
+    );
+
+    // Records read whole are checked once read.
+    let records = format!(
+        "type, client, tx, amount\ndeposit, 1, 1, {}\n",
+        "9".repeat(1 << 20)
+    );
+    let transaction_records = koncord::reader_builder().from_reader(records.as_bytes());
+    assert_eq!(
+        koncord::validate_schema(transaction_records),
+        Err(vec![SchemaError::RecordTooLarge { line: 2 }])
+    );
+}
@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use koncord::Engine;
+
+#[test]
+fn invariants_hold() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/complex.csv");
+
+    let mut engine = Engine::new();
+    engine
+        .process(koncord::reader_builder().from_path(&records_path).unwrap())
+        .unwrap();
+
+    assert!(engine.stats().invariant_violations.is_empty());
+}
+
+// Corrupt clients are only built with the `testing` feature.
+#[cfg(feature = "testing")]
+#[test]
+fn invariants_violated() {
+    use std::collections::HashMap;
+
+    use rust_decimal::Decimal;
+
+    use koncord::client::ClientSnapshot;
+    use koncord::testing::corrupt_client;
+
+    let corrupt = corrupt_client(ClientSnapshot {
+        client: 2,
+        available: Decimal::ONE,
+        held: Decimal::ONE,
+        total: Decimal::ONE,
+        locked: false,
+        flagged: false,
+    });
+
+    let mut engine = Engine::new().with_clients(HashMap::from([(2, corrupt)]));
+    engine
+        .process(
+            koncord::reader_builder()
+                .from_reader("type,client,tx,amount\ndeposit,1,1,1.0\n".as_bytes()),
+        )
+        .unwrap();
+
+    assert_eq!(engine.stats().invariant_violations, vec![2]);
+}
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use koncord::{Engine, Warning};
+
+fn koncord(args: &[&str]) -> std::process::Output {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/insufficient_funds.csv");
+
+    Command::new(env!("CARGO_BIN_EXE_koncord"))
+        .args(args)
+        .arg(&records_path)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn json_warnings() {
+    let output = koncord(&["--json-warnings"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "{\"level\":\"warn\",\"tx\":2,\"reason\":\"insufficient_funds\"}\n"
+    );
+    // Warnings are kept out of the results.
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "client,available,held,total,locked\n1,1,0.0000,1,false\n"
+    );
+}
+
+#[test]
+fn json_warnings_unset() {
+    let output = koncord(&[]);
+
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn warnings() {
+    let records = "\
+type,       client, tx, amount
+deposit,    1,      1,  0.0
+deposit,    1,      2,  3.0
+transfer,   1,      3,  1.0
+dispute,    1,      2,
+withdrawal, 1,      4,  2.0
+";
+    let transaction_records = koncord::reader_builder().from_reader(records.as_bytes());
+
+    let mut engine = Engine::new().warnings_enabled(true);
+    engine.process(transaction_records).unwrap();
+
+    let warning = |tx, reason| Warning {
+        client: 1,
+        tx,
+        reason,
+    };
+    assert_eq!(
+        engine.warnings(),
+        [
+            warning(1, "zero_amount"),
+            warning(3, "unknown_kind"),
+            warning(4, "funds_held"),
+        ]
+    );
+}
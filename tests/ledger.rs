@@ -0,0 +1,51 @@
+use rust_decimal::Decimal;
+
+use koncord::{Engine, LedgerEntry, TransactionKind};
+
+const MEMOS: &str = "\
+type,       client, tx, amount, description
+deposit,    1,      1,  2.0,    Opening balance
+withdrawal, 1,      2,  1.0
+dispute,    1,      1,  ,\"Customer claims fraud, ticket 42\"
+";
+
+#[test]
+fn ledger_description() {
+    let transaction_records = koncord::reader_builder().from_reader(MEMOS.as_bytes());
+
+    let mut engine = Engine::new().ledger_enabled(true);
+    engine.process(transaction_records).unwrap();
+
+    let ledger = engine.ledger();
+    assert_eq!(ledger.len(), 3);
+    assert_eq!(
+        ledger[0],
+        LedgerEntry {
+            client: 1,
+            tx: 1,
+            kind: TransactionKind::Deposit,
+            amount: Some(Decimal::new(2, 0)),
+            description: Some("Opening balance".to_string()),
+        }
+    );
+    assert_eq!(ledger[1].description, None);
+    assert_eq!(
+        ledger[2].description.as_deref(),
+        Some("Customer claims fraud, ticket 42")
+    );
+
+    // Memos do not affect balances.
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::new(-1, 0));
+    assert_eq!(account.held(), Decimal::new(2, 0));
+}
+
+#[test]
+fn ledger_disabled() {
+    let transaction_records = koncord::reader_builder().from_reader(MEMOS.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    assert!(engine.ledger().is_empty());
+}
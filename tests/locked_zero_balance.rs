@@ -0,0 +1,51 @@
+use koncord::client::LockedZeroBalance;
+use koncord::Engine;
+
+// Client 1 is charged back to a zero balance while client 2 keeps funds once
+// locked.
+const RECORDS: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+dispute,    1,      1,
+chargeback, 1,      1,
+deposit,    2,      2,  1.0
+deposit,    2,      3,  2.0
+dispute,    2,      3,
+chargeback, 2,      3,
+deposit,    3,      4,  1.0
+";
+
+fn output(treatment: LockedZeroBalance) -> String {
+    let mut engine = Engine::new().locked_zero_balance(treatment);
+    engine
+        .process(koncord::reader_builder().from_reader(RECORDS.as_bytes()))
+        .unwrap();
+
+    let mut output = vec![];
+    engine.write(&mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn locked_zero_balance() {
+    assert_eq!(
+        output(LockedZeroBalance::Locked),
+        "client,available,held,total,locked\n1,0,0,0,true\n2,1,0,1,true\n3,1,0.0000,1,false\n"
+    );
+}
+
+#[test]
+fn locked_zero_balance_closed() {
+    assert_eq!(
+        output(LockedZeroBalance::Closed),
+        "client,available,held,total,locked\n1,0,0,0,closed\n2,1,0,1,true\n3,1,0.0000,1,false\n"
+    );
+}
+
+#[test]
+fn locked_zero_balance_omit() {
+    assert_eq!(
+        output(LockedZeroBalance::Omit),
+        "client,available,held,total,locked\n2,1,0,1,true\n3,1,0.0000,1,false\n"
+    );
+}
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use rust_decimal::Decimal;
+
+use koncord::client::Client;
+use koncord::{Engine, KoncordError};
+
+// The records dispute transaction 2 which the lookup records lack.
+const RECORDS: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+deposit,    1,      2,  2.0
+dispute,    1,      2,
+";
+
+#[test]
+fn lookup_source_mismatch() {
+    let mut lookup_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    lookup_path.push("tests/data/lookup_missing.csv");
+
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    let err = koncord::run(
+        &mut clients,
+        koncord::reader_builder().from_reader(RECORDS.as_bytes()),
+        lookup_path.to_str().unwrap(),
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        err.downcast_ref::<KoncordError>(),
+        Some(&KoncordError::LookupSourceMismatch { tx: 2 })
+    );
+}
+
+#[test]
+fn engine_lookup_path() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/complex.csv");
+    let records_path = records_path.to_str().unwrap();
+
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    koncord::run(
+        &mut clients,
+        koncord::reader_builder().from_path(records_path).unwrap(),
+        records_path,
+    )
+    .unwrap();
+    let mut engine = Engine::new().lookup_path(records_path);
+    engine
+        .process(koncord::reader_builder().from_path(records_path).unwrap())
+        .unwrap();
+
+    // The dispute of absent transaction 8 holds its own amount as with `run`.
+    assert_eq!(engine.client(1).unwrap().get().held(), Decimal::new(5, 1));
+    assert_eq!(engine.into_clients(), clients);
+}
@@ -0,0 +1,40 @@
+use rust_decimal::Decimal;
+
+use koncord::Engine;
+
+const RECORDS: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  79228162514264337593543950.335
+deposit,    1,      2,  250.0
+deposit,    1,      3,  1000.0001
+adjustment, 1,      4,  -5000
+dispute,    1,      1,
+";
+
+#[test]
+fn max_amount() {
+    let transaction_records = koncord::reader_builder().from_reader(RECORDS.as_bytes());
+
+    let mut engine = Engine::new().max_amount(Decimal::new(1000, 0));
+    engine.process(transaction_records).unwrap();
+
+    let stats = engine.stats();
+    assert_eq!(stats.over_max_amount, 3);
+    assert_eq!(stats.rejected(), 3);
+    assert_eq!(stats.applied, 1);
+
+    // The refused deposit was never indexed so cannot be disputed.
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::new(250, 0));
+    assert_eq!(account.held(), Decimal::ZERO);
+}
+
+#[test]
+fn max_amount_unset() {
+    let transaction_records = koncord::reader_builder().from_reader(RECORDS.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    assert_eq!(engine.stats().over_max_amount, 0);
+}
@@ -0,0 +1,47 @@
+use rust_decimal::Decimal;
+
+use koncord::Engine;
+
+// The second dispute of client 1 is refused while the first is open, once
+// resolved the third dispute is accepted. Client 2 has its own limit.
+const DISPUTES: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+deposit,    1,      2,  2.0
+deposit,    1,      3,  4.0
+deposit,    2,      4,  8.0
+dispute,    1,      1,
+dispute,    1,      2,
+dispute,    2,      4,
+resolve,    1,      1,
+dispute,    1,      3,
+";
+
+#[test]
+fn max_open_disputes() {
+    let mut engine = Engine::new().max_open_disputes_per_client(1);
+    engine
+        .process(koncord::reader_builder().from_reader(DISPUTES.as_bytes()))
+        .unwrap();
+
+    let client = engine.client(1).unwrap();
+    assert_eq!(client.get().held(), Decimal::new(4, 0));
+    assert_eq!(client.get().available(), Decimal::new(3, 0));
+    assert_eq!(client.open_dispute_count(), 1);
+    assert_eq!(engine.client(2).unwrap().get().held(), Decimal::new(8, 0));
+    assert_eq!(engine.stats().disputes_over_limit, 1);
+    assert_eq!(engine.stats().rejected(), 1);
+}
+
+#[test]
+fn max_open_disputes_unset() {
+    let mut engine = Engine::new();
+    engine
+        .process(koncord::reader_builder().from_reader(DISPUTES.as_bytes()))
+        .unwrap();
+
+    let client = engine.client(1).unwrap();
+    assert_eq!(client.get().held(), Decimal::new(6, 0));
+    assert_eq!(client.open_dispute_count(), 2);
+    assert_eq!(engine.stats().disputes_over_limit, 0);
+}
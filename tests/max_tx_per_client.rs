@@ -0,0 +1,32 @@
+use rust_decimal::Decimal;
+
+use koncord::Engine;
+
+const RECORDS: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+deposit,    1,      2,  1.0
+deposit,    2,      3,  1.0
+withdrawal, 1,      4,  0.5
+deposit,    1,      5,  1.0
+deposit,    2,      6,  1.0
+";
+
+#[test]
+fn max_tx_per_client() {
+    let mut engine = Engine::new().max_tx_per_client(3);
+    engine
+        .process(koncord::reader_builder().from_reader(RECORDS.as_bytes()))
+        .unwrap();
+
+    assert_eq!(
+        engine.client(1).unwrap().get().available(),
+        Decimal::new(15, 1)
+    );
+    assert_eq!(
+        engine.client(2).unwrap().get().available(),
+        Decimal::new(2, 0)
+    );
+    assert_eq!(engine.stats().capped, 1);
+    assert_eq!(engine.stats().rejected(), 1);
+}
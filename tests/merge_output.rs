@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+// Client 4 is locked in the prior output so its deposit is skipped.
+const EXPECTED: &str = "\
+client,available,held,total,locked
+1,12.5,0,12.5,false
+2,1,0.0000,1,false
+3,4,0,4,false
+4,1,0,1,true
+";
+
+#[test]
+fn merge_output() {
+    let data = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_koncord"))
+        .arg("--merge-output")
+        .arg(data.join("prior_accounts.csv"))
+        .arg(data.join("merge.csv"))
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), EXPECTED);
+}
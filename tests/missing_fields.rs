@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use koncord::client::Client;
+use koncord::{Engine, KoncordError};
+
+const MISSING_TX: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+deposit,    1,      ,   2.0
+";
+
+const MALFORMED_TX: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+deposit,    1,      x,  2.0
+";
+
+#[test]
+fn missing_tx() {
+    let transaction_records = koncord::reader_builder().from_reader(MISSING_TX.as_bytes());
+
+    let mut engine = Engine::new();
+    let err = engine.process(transaction_records).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<KoncordError>(),
+        Some(&KoncordError::MissingTx { line: 3 })
+    );
+    assert_eq!(err.to_string(), "Missing transaction ID on line 3");
+}
+
+#[test]
+fn missing_tx_run() {
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    let transaction_records =
+        koncord::reader_builder().from_reader(Cursor::new(MISSING_TX.as_bytes()));
+
+    let err = koncord::run(&mut clients, transaction_records, "").unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<KoncordError>(),
+        Some(&KoncordError::MissingTx { line: 3 })
+    );
+}
+
+#[test]
+fn malformed_tx() {
+    let transaction_records = koncord::reader_builder().from_reader(MALFORMED_TX.as_bytes());
+
+    let mut engine = Engine::new();
+    let err = engine.process(transaction_records).unwrap_err();
+    assert!(err.downcast_ref::<KoncordError>().is_none());
+    assert!(err.downcast_ref::<csv::Error>().is_some());
+}
+
+const MISSING_AMOUNT: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+dispute,    1,      1
+deposit,    1,      2
+";
+
+#[test]
+fn missing_amount() {
+    let transaction_records = koncord::reader_builder().from_reader(MISSING_AMOUNT.as_bytes());
+
+    let mut engine = Engine::new();
+    let err = engine.process(transaction_records).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<KoncordError>(),
+        Some(&KoncordError::MissingAmount { line: 4 })
+    );
+    assert_eq!(err.to_string(), "Missing amount on line 4");
+}
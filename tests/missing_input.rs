@@ -0,0 +1,15 @@
+use std::process::Command;
+
+#[test]
+fn missing_input() {
+    let output = Command::new(env!("CARGO_BIN_EXE_koncord"))
+        .arg("tests/data/does_not_exist.csv")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("error: cannot open input file tests/data/does_not_exist.csv: "));
+}
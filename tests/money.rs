@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use rust_decimal::Decimal;
+
+use koncord::testing::assert_balances_eq;
+use koncord::{Engine, MinorUnits, Money};
+
+fn records_path(name: &str) -> PathBuf {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data");
+    records_path.push(name);
+    records_path
+}
+
+// Process `name` with balances kept in `M`, returning the written clients.
+fn process<M: Money>(mut engine: Engine<M>, name: &str) -> (String, Engine<M>) {
+    let transaction_records = koncord::reader_builder()
+        .from_path(records_path(name))
+        .unwrap();
+    engine.process(transaction_records).unwrap();
+
+    let mut output = Vec::new();
+    engine.write(&mut output).unwrap();
+    (String::from_utf8(output).unwrap(), engine)
+}
+
+#[test]
+fn money_backends_equal() {
+    for name in [
+        "complex.csv",
+        "correction.csv",
+        "partial_resolves.csv",
+        "reversal.csv",
+        "toy/chargeback.csv",
+    ] {
+        let (decimal, decimal_engine) = process(Engine::new(), name);
+        let (minor, minor_engine) = process(Engine::with_money::<MinorUnits>(), name);
+
+        assert_balances_eq(&minor, &decimal);
+        assert_eq!(minor_engine.stats(), decimal_engine.stats(), "{name}");
+    }
+}
+
+// Minor units cannot represent a fraction of a ten-thousandth, the deposit is
+// refused unless rounded first.
+const DUST: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.00005
+";
+
+#[test]
+fn minor_units_refuse_dust() {
+    let mut engine = Engine::with_money::<MinorUnits>();
+    engine
+        .process(koncord::reader_builder().from_reader(DUST.as_bytes()))
+        .unwrap();
+
+    assert_eq!(engine.client(1).unwrap().get().total(), Decimal::ZERO);
+    assert_eq!(engine.stats().skipped, 1);
+
+    let mut engine =
+        Engine::with_money::<MinorUnits>().rounding(koncord::client::Rounding::HalfEven);
+    engine
+        .process(koncord::reader_builder().from_reader(DUST.as_bytes()))
+        .unwrap();
+
+    assert_eq!(engine.client(1).unwrap().get().total(), Decimal::ONE);
+    assert_eq!(engine.client(1).unwrap().balance().total().scale(), 4);
+}
+
+// The second deposit overflows the `i64` count of minor units, it is refused
+// leaving the balance as is.
+const OVERFLOW: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  900000000000000
+deposit,    1,      2,  900000000000000
+";
+
+#[test]
+fn minor_units_refuse_overflow() {
+    let mut engine = Engine::with_money::<MinorUnits>();
+    engine
+        .process(koncord::reader_builder().from_reader(OVERFLOW.as_bytes()))
+        .unwrap();
+
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::new(900_000_000_000_000, 0));
+    assert_eq!(account.total(), Decimal::new(900_000_000_000_000, 0));
+    assert_eq!(engine.stats().skipped, 1);
+}
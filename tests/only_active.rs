@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use koncord::client::{load_clients, ClientSnapshot};
+use koncord::Engine;
+
+// Client 3 and 4 are carried over without any records so are left out.
+const EXPECTED: &str = "\
+client,available,held,total,locked
+1,12.5,0,12.5,false
+2,1,0.0000,1,false
+";
+
+fn data() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data")
+}
+
+#[test]
+fn only_active() {
+    let mut reader = koncord::reader_builder()
+        .from_path(data().join("prior_accounts.csv"))
+        .unwrap();
+    let snapshots: Vec<ClientSnapshot> = reader.deserialize().map(Result::unwrap).collect();
+
+    let mut engine = Engine::new()
+        .with_clients(load_clients(snapshots).unwrap())
+        .only_active(true);
+    let transaction_records = koncord::reader_builder()
+        .from_path(data().join("only_active.csv"))
+        .unwrap();
+    engine.process(transaction_records).unwrap();
+    assert!(engine.contains_client(3));
+
+    let mut output = vec![];
+    engine.write(&mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), EXPECTED);
+}
+
+#[test]
+fn only_active_cli() {
+    let output = Command::new(env!("CARGO_BIN_EXE_koncord"))
+        .arg("--only-active")
+        .arg("--merge-output")
+        .arg(data().join("prior_accounts.csv"))
+        .arg(data().join("only_active.csv"))
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), EXPECTED);
+}
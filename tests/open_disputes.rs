@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use koncord::{Engine, OpenDispute};
+
+const DISPUTED: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+deposit,    1,      2,  2.0
+dispute,    1,      1,
+";
+
+const RESOLVED: &str = "\
+type,       client, tx, amount
+resolve,    1,      1,
+";
+
+// Process `DISPUTED` then resume its open dispute in a new engine.
+fn resumed(engine: Engine) -> Engine {
+    let mut disputed = Engine::new();
+    disputed
+        .process(koncord::reader_builder().from_reader(DISPUTED.as_bytes()))
+        .unwrap();
+
+    let mut output = vec![];
+    disputed.write_open_disputes(&mut output).unwrap();
+    let disputes: Vec<OpenDispute> = csv::Reader::from_reader(output.as_slice())
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    engine
+        .with_clients(disputed.into_clients())
+        .with_open_disputes(disputes)
+}
+
+#[test]
+fn open_disputes_export() {
+    let mut engine = Engine::new();
+    engine
+        .process(koncord::reader_builder().from_reader(DISPUTED.as_bytes()))
+        .unwrap();
+    assert_eq!(
+        engine.open_disputes(),
+        &HashMap::from([(1, Decimal::new(10, 1))])
+    );
+
+    let mut output = vec![];
+    engine.write_open_disputes(&mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output.clone()).unwrap(),
+        "tx,client,amount\n1,1,1\n"
+    );
+
+    // A follow-up batch resumes the dispute and resolves it.
+    let mut resumed = resumed(Engine::new());
+    assert_eq!(resumed.client(1).unwrap().open_dispute_count(), 1);
+    assert_eq!(
+        resumed.client(1).unwrap().get().held_breakdown(),
+        &HashMap::from([(1, Decimal::new(10, 1))])
+    );
+    resumed
+        .process(koncord::reader_builder().from_reader(RESOLVED.as_bytes()))
+        .unwrap();
+
+    let account = resumed.client(1).unwrap().get();
+    assert_eq!(account.held(), Decimal::ZERO);
+    assert_eq!(account.available(), Decimal::new(3, 0));
+    assert!(resumed.open_disputes().is_empty());
+    assert_eq!(resumed.client(1).unwrap().open_dispute_count(), 0);
+    assert!(account.held_breakdown().is_empty());
+}
+
+#[test]
+fn open_disputes_resumed_limit() {
+    let records = "\
+type,       client, tx, amount
+deposit,    1,      3,  4.0
+dispute,    1,      3,
+";
+    let mut resumed = resumed(Engine::new().max_open_disputes_per_client(1));
+    resumed
+        .process(koncord::reader_builder().from_reader(records.as_bytes()))
+        .unwrap();
+
+    // The resumed dispute counts towards the limit.
+    assert_eq!(resumed.stats().disputes_over_limit, 1);
+    assert_eq!(resumed.client(1).unwrap().get().held(), Decimal::new(10, 1));
+}
+
+#[test]
+fn open_disputes_none() {
+    let mut output = vec![];
+    Engine::new().write_open_disputes(&mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), "tx,client,amount\n");
+}
@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use koncord::Engine;
+
+#[test]
+fn ordered_clients() {
+    let mut records = String::from("type, client, tx, amount\n");
+    for (tx, id) in [7, 3, 65535, 0, 42, 3, 1000].iter().enumerate() {
+        records += &format!("deposit, {id}, {tx}, 1.0\n");
+    }
+    let transaction_records = koncord::reader_builder().from_reader(records.as_bytes());
+
+    let mut engine = Engine::new().ordered_clients(true);
+    engine.process(transaction_records).unwrap();
+
+    let ids: Vec<u16> = engine.clients().map(|client| client.id()).collect();
+    assert_eq!(ids, vec![0, 3, 7, 42, 1000, 65535]);
+}
+
+#[test]
+fn ordered_clients_write() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/toy/base.csv");
+
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    let mut engine = Engine::new().ordered_clients(true);
+    engine.process(transaction_records).unwrap();
+
+    let mut wtr = csv::Writer::from_writer(vec![]);
+    for client in engine.clients() {
+        wtr.serialize(client).unwrap();
+    }
+    let mut written = vec![];
+    engine.write(&mut written).unwrap();
+
+    assert_eq!(wtr.into_inner().unwrap(), written);
+}
@@ -0,0 +1,28 @@
+use rust_decimal::Decimal;
+
+use koncord::Engine;
+
+const ORPHAN: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  100.0
+dispute,    1,      1,
+resolve,    1,      1,
+chargeback, 1,      1,
+";
+
+#[test]
+fn orphan_chargeback() {
+    let mut engine = Engine::new();
+    engine
+        .process(koncord::reader_builder().from_reader(ORPHAN.as_bytes()))
+        .unwrap();
+
+    let client = engine.client(1).unwrap();
+    assert_eq!(client.get().available(), Decimal::new(100, 0));
+    assert_eq!(client.get().held(), Decimal::ZERO);
+    assert_eq!(client.get().total(), Decimal::new(100, 0));
+    assert!(!client.is_locked());
+    assert_eq!(client.get().activity().chargeback_shortfall, Decimal::ZERO);
+    assert_eq!(engine.stats().orphan_chargebacks, 1);
+    assert_eq!(engine.stats().applied, 3);
+}
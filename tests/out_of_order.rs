@@ -0,0 +1,35 @@
+use rust_decimal::Decimal;
+
+use koncord::Engine;
+
+const OUT_OF_ORDER: &str = "\
+type,    client, tx, amount, description, timestamp
+deposit, 1,      1,  1.0,    ,            1700000100
+deposit, 1,      2,  2.0,    ,            1700000000
+deposit, 2,      3,  4.0,    ,            1700000000
+deposit, 1,      4,  8.0
+";
+
+#[test]
+fn out_of_order_rejected() {
+    let transaction_records = koncord::reader_builder().from_reader(OUT_OF_ORDER.as_bytes());
+
+    let mut engine = Engine::new().reject_out_of_order(true);
+    engine.process(transaction_records).unwrap();
+
+    assert_eq!(engine.client(1).unwrap().get().total(), Decimal::new(9, 0));
+    assert_eq!(engine.client(2).unwrap().get().total(), Decimal::new(4, 0));
+    assert_eq!(engine.stats().out_of_order, 1);
+    assert_eq!(engine.stats().applied, 3);
+}
+
+#[test]
+fn out_of_order_accepted() {
+    let transaction_records = koncord::reader_builder().from_reader(OUT_OF_ORDER.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    assert_eq!(engine.client(1).unwrap().get().total(), Decimal::new(11, 0));
+    assert_eq!(engine.stats().out_of_order, 0);
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use koncord::client::Client;
+use koncord::Engine;
+use rust_decimal::Decimal;
+
+#[test]
+fn partial_resolve() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/partial_resolve.csv");
+
+    let mut clients: HashMap<u16, Client> = HashMap::with_capacity(usize::from(u16::MAX));
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    koncord::run(
+        &mut clients,
+        transaction_records,
+        records_path.to_str().unwrap(),
+    )
+    .unwrap();
+
+    let account = clients[&1].get();
+    assert_eq!(account.available(), Decimal::new(400, 1));
+    assert_eq!(account.held(), Decimal::new(600, 1));
+    assert_eq!(account.total(), Decimal::new(1000, 1));
+}
+
+#[test]
+fn partial_resolve_engine() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/partial_resolve.csv");
+
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    // The remaining 60.0 stays disputed and is released by a full resolve.
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.held(), Decimal::new(600, 1));
+
+    let transaction_records = koncord::reader_builder()
+        .from_reader("type, client, tx, amount\nresolve, 1, 1,\n".as_bytes());
+    engine.process(transaction_records).unwrap();
+
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::new(1000, 1));
+    assert_eq!(account.held(), Decimal::ZERO);
+}
+
+#[test]
+fn partial_resolves_over_release() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/partial_resolves.csv");
+
+    // Resolving 60.0 leaves 40.0 held, resolving 50.0 then releases only the
+    // 40.0 remaining and closes the dispute.
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::new(1000, 1));
+    assert_eq!(account.held(), Decimal::ZERO);
+    assert_eq!(account.total(), Decimal::new(1000, 1));
+    assert!(engine.open_disputes().is_empty());
+    assert_eq!(engine.stats().applied, 4);
+
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    koncord::run(
+        &mut clients,
+        transaction_records,
+        records_path.to_str().unwrap(),
+    )
+    .unwrap();
+
+    let account = clients[&1].get();
+    assert_eq!(account.available(), Decimal::new(1000, 1));
+    assert_eq!(account.held(), Decimal::ZERO);
+}
@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use koncord::Engine;
+
+fn records_path() -> PathBuf {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/complex.csv");
+    records_path
+}
+
+fn output(engine: &Engine) -> String {
+    let mut output = vec![];
+    engine.write(&mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn process_chunk() {
+    let mut full = Engine::new();
+    full.process(koncord::reader_builder().from_path(records_path()).unwrap())
+        .unwrap();
+
+    let mut transaction_records = koncord::reader_builder().from_path(records_path()).unwrap();
+    let mut chunked = Engine::new();
+    let mut chunks = vec![];
+    loop {
+        let count = chunked.process_chunk(&mut transaction_records, 2).unwrap();
+        if count == 0 {
+            break;
+        }
+        chunks.push(count);
+    }
+
+    assert!(chunks.iter().all(|count| *count <= 2));
+    assert_eq!(chunks.iter().sum::<usize>(), full.stats().records);
+    assert_eq!(chunks.len(), full.stats().records.div_ceil(2));
+    assert_eq!(chunked.stats(), full.stats());
+    assert_eq!(output(&chunked), output(&full));
+}
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use koncord::client::Client;
+
+#[test]
+fn progress() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/funds.csv");
+
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    let mut reports = vec![];
+    koncord::run_with_progress(
+        &mut clients,
+        transaction_records,
+        records_path.to_str().unwrap(),
+        4,
+        |records, _elapsed| reports.push(records),
+    )
+    .unwrap();
+
+    // 14 records report after the 4th, 8th and 12th.
+    assert_eq!(reports, vec![4, 8, 12]);
+    assert_eq!(clients.len(), 3);
+}
+
+#[test]
+fn progress_never() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/funds.csv");
+
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    let mut reports = 0;
+    koncord::run_with_progress(
+        &mut clients,
+        transaction_records,
+        records_path.to_str().unwrap(),
+        0,
+        |_, _| reports += 1,
+    )
+    .unwrap();
+
+    assert_eq!(reports, 0);
+}
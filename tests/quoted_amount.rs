@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use rust_decimal::Decimal;
+
+use koncord::client::Client;
+use koncord::Engine;
+
+fn records_path() -> PathBuf {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/quoted_amount.csv");
+    records_path
+}
+
+#[test]
+fn quoted_amount() {
+    let mut engine = Engine::new();
+    engine
+        .process(koncord::reader_builder().from_path(records_path()).unwrap())
+        .unwrap();
+
+    let client = |id| engine.client(id).unwrap().get();
+    assert_eq!(client(1).available(), Decimal::new(-25, 2));
+    assert_eq!(client(1).held(), Decimal::new(100, 0));
+    assert_eq!(client(2).available(), Decimal::new(25, 1));
+}
+
+#[test]
+fn quoted_amount_run() {
+    let records_path = records_path();
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    koncord::run(
+        &mut clients,
+        transaction_records,
+        records_path.to_str().unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(clients[&1].get().available(), Decimal::new(-25, 2));
+    assert_eq!(clients[&1].get().held(), Decimal::new(100, 0));
+    assert_eq!(clients[&2].get().available(), Decimal::new(25, 1));
+}
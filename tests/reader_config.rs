@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use rust_decimal::Decimal;
+
+use koncord::client::Client;
+use koncord::ReaderConfig;
+
+#[test]
+fn reader_config_semicolon() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/semicolon.csv");
+
+    let config = ReaderConfig {
+        delimiter: b';',
+        ..ReaderConfig::default()
+    };
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    koncord::run_with_config(&mut clients, records_path.to_str().unwrap(), &config).unwrap();
+
+    let account = clients[&1].get();
+    assert_eq!(account.available(), Decimal::ZERO);
+    assert_eq!(account.held(), Decimal::new(15, 1));
+
+    let account = clients[&2].get();
+    assert_eq!(account.available(), Decimal::ONE);
+}
+
+#[test]
+fn reader_config_without_headers() {
+    let config = ReaderConfig {
+        has_headers: false,
+        ..ReaderConfig::default()
+    };
+    let transaction_records = config
+        .builder()
+        .from_reader("deposit, 1, 1, 1.5\nwithdrawal, 1, 2, 0.5\n".as_bytes());
+
+    let mut engine = koncord::Engine::new();
+    engine.process(transaction_records).unwrap();
+    assert_eq!(engine.client(1).unwrap().get().available(), Decimal::ONE);
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use koncord::client::{Client, ClientSnapshot};
+use koncord::{reconcile, ClientDiff};
+
+fn clients(deposits: &[(u16, Decimal)]) -> HashMap<u16, Client> {
+    let mut clients = HashMap::new();
+    for &(id, amount) in deposits {
+        let client = clients.entry(id).or_insert(Client::new(id));
+        client.get_mut().adjust(amount);
+    }
+    clients
+}
+
+fn snapshot(client: u16, total: Decimal) -> ClientSnapshot {
+    ClientSnapshot {
+        client,
+        available: total,
+        held: Decimal::ZERO,
+        total,
+        locked: false,
+        flagged: false,
+    }
+}
+
+#[test]
+fn reconcile_equal() {
+    let a = clients(&[(1, Decimal::ONE), (2, Decimal::TEN)]);
+    let b = clients(&[(2, Decimal::TEN), (1, Decimal::new(10, 1))]);
+
+    assert_eq!(reconcile(&a, &b), []);
+}
+
+#[test]
+fn reconcile_differences() {
+    let a = clients(&[(1, Decimal::ONE), (2, Decimal::TEN), (3, Decimal::ONE)]);
+    let b = clients(&[(1, Decimal::ONE), (2, Decimal::ONE), (4, Decimal::ONE)]);
+
+    assert_eq!(
+        reconcile(&a, &b),
+        [
+            ClientDiff {
+                client: 2,
+                left: Some(snapshot(2, Decimal::TEN)),
+                right: Some(snapshot(2, Decimal::ONE)),
+            },
+            ClientDiff {
+                client: 3,
+                left: Some(snapshot(3, Decimal::ONE)),
+                right: None,
+            },
+            ClientDiff {
+                client: 4,
+                left: None,
+                right: Some(snapshot(4, Decimal::ONE)),
+            },
+        ]
+    );
+}
@@ -0,0 +1,31 @@
+use koncord::Engine;
+
+const RECORDS: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  5.0
+withdrawal, 1,      2,  10.0
+deposit,    2,      3,  0.0
+transfer,   2,      4,  1.0
+deposit,    2,      5,  2.0
+dispute,    2,      5,
+";
+
+#[test]
+fn record_counters() {
+    let mut transaction_records = koncord::reader_builder().from_reader(RECORDS.as_bytes());
+    let mut engine = Engine::new();
+    assert_eq!(engine.records_seen(), 0);
+
+    // Counters update between chunks.
+    engine.process_chunk(&mut transaction_records, 2).unwrap();
+    assert_eq!(engine.records_seen(), 2);
+    assert_eq!(engine.records_applied(), 1);
+    assert_eq!(engine.records_skipped(), 1);
+
+    while engine.process_chunk(&mut transaction_records, 2).unwrap() > 0 {}
+    // The zero amount deposit and unknown transfer are skipped as well as
+    // the withdrawal of insufficient funds.
+    assert_eq!(engine.records_seen(), 6);
+    assert_eq!(engine.records_applied(), 3);
+    assert_eq!(engine.records_skipped(), 3);
+}
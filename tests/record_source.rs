@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use koncord::client::Client;
+use koncord::{KoncordError, Record, RecordSource};
+
+// Records held in memory, standing in for a database cursor.
+struct VecSource {
+    records: std::vec::IntoIter<Record>,
+}
+
+impl RecordSource for VecSource {
+    fn next_record(&mut self) -> Result<Option<Record>, KoncordError> {
+        Ok(self.records.next())
+    }
+}
+
+#[test]
+fn record_source_matches_csv() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/complex.csv");
+    let records_path = records_path.to_str().unwrap();
+
+    let mut csv_clients: HashMap<u16, Client> = HashMap::new();
+    let transaction_records = koncord::reader_builder().from_path(records_path).unwrap();
+    koncord::run(&mut csv_clients, transaction_records, records_path).unwrap();
+
+    let records: Vec<Record> = koncord::reader_builder()
+        .from_path(records_path)
+        .unwrap()
+        .deserialize()
+        .map(Result::unwrap)
+        .collect();
+    let source = VecSource {
+        records: records.into_iter(),
+    };
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    koncord::run(&mut clients, source, records_path).unwrap();
+
+    assert_eq!(clients.len(), 5);
+    assert_eq!(clients, csv_clients);
+}
@@ -0,0 +1,73 @@
+use rust_decimal::Decimal;
+
+use koncord::Engine;
+
+// The dispute of tx 1 is resolved then disputed again, a dispute of tx 2
+// never disputed before is accepted either way.
+const REDISPUTE: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+deposit,    1,      2,  2.0
+dispute,    1,      1,
+resolve,    1,      1,
+dispute,    1,      1,
+dispute,    1,      2,
+";
+
+#[test]
+fn redispute_allowed() {
+    let mut engine = Engine::new();
+    engine
+        .process(koncord::reader_builder().from_reader(REDISPUTE.as_bytes()))
+        .unwrap();
+
+    let client = engine.client(1).unwrap();
+    assert_eq!(client.get().held(), Decimal::new(3, 0));
+    assert_eq!(client.get().available(), Decimal::ZERO);
+    assert_eq!(engine.dispute_count(1), 2);
+    assert_eq!(engine.dispute_count(2), 1);
+    assert_eq!(engine.stats().redisputes_refused, 0);
+}
+
+#[test]
+fn redispute_refused() {
+    let mut engine = Engine::new().allow_redispute(false);
+    engine
+        .process(koncord::reader_builder().from_reader(REDISPUTE.as_bytes()))
+        .unwrap();
+
+    let client = engine.client(1).unwrap();
+    assert_eq!(client.get().held(), Decimal::new(2, 0));
+    assert_eq!(client.get().available(), Decimal::new(1, 0));
+    assert_eq!(engine.dispute_count(1), 1);
+    assert_eq!(engine.dispute_count(2), 1);
+    assert!(!engine.open_disputes().contains_key(&1));
+    assert_eq!(engine.stats().redisputes_refused, 1);
+    assert_eq!(engine.stats().rejected(), 1);
+}
+
+#[test]
+fn redispute_open() {
+    let records = "\
+type,       client, tx, amount
+deposit,    1,      1,  100.0
+dispute,    1,      1,
+dispute,    1,      1,
+";
+
+    for allow in [true, false] {
+        let mut engine = Engine::new().allow_redispute(allow);
+        engine
+            .process(koncord::reader_builder().from_reader(records.as_bytes()))
+            .unwrap();
+
+        // The second dispute is refused while the first is open.
+        let client = engine.client(1).unwrap();
+        assert_eq!(client.get().available(), Decimal::ZERO);
+        assert_eq!(client.get().held(), Decimal::new(100, 0));
+        assert_eq!(client.get().total(), Decimal::new(100, 0));
+        assert_eq!(engine.open_disputes()[&1], Decimal::new(100, 0));
+        assert_eq!(engine.dispute_count(1), 1);
+        assert_eq!(engine.stats().redisputes_refused, 1);
+    }
+}
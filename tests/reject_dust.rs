@@ -0,0 +1,57 @@
+use rust_decimal::Decimal;
+
+use koncord::Engine;
+
+const DUST: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+withdrawal, 1,      2,  0.99999
+withdrawal, 1,      3,  0.5
+deposit,    2,      4,  1.0
+withdrawal, 2,      5,  0.005
+";
+
+#[test]
+fn reject_dust() {
+    let mut engine = Engine::new().reject_dust(true);
+    engine
+        .process(koncord::reader_builder().from_reader(DUST.as_bytes()))
+        .unwrap();
+
+    assert_eq!(
+        engine.client(1).unwrap().get().available(),
+        Decimal::new(5, 1)
+    );
+    assert_eq!(
+        engine.client(2).unwrap().get().available(),
+        Decimal::new(995, 3)
+    );
+    assert_eq!(engine.stats().dust_withdrawals, 1);
+    assert_eq!(engine.stats().rejected(), 1);
+}
+
+#[test]
+fn reject_dust_scale() {
+    let mut engine = Engine::new().with_scale(2).reject_dust(true);
+    engine
+        .process(koncord::reader_builder().from_reader(DUST.as_bytes()))
+        .unwrap();
+
+    assert_eq!(engine.client(2).unwrap().get().available(), Decimal::ONE);
+    assert_eq!(engine.stats().dust_withdrawals, 2);
+}
+
+#[test]
+fn reject_dust_unset() {
+    let mut engine = Engine::new();
+    engine
+        .process(koncord::reader_builder().from_reader(DUST.as_bytes()))
+        .unwrap();
+
+    // The dust is left behind and the next withdrawal refused.
+    assert_eq!(
+        engine.client(1).unwrap().get().available(),
+        Decimal::new(1, 5)
+    );
+    assert_eq!(engine.stats().dust_withdrawals, 0);
+}
@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use rust_decimal::Decimal;
+
+use koncord::client::Client;
+use koncord::TransactionReport;
+
+#[test]
+fn report_complex() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/complex.csv");
+
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    koncord::run(
+        &mut clients,
+        transaction_records,
+        records_path.to_str().unwrap(),
+    )
+    .unwrap();
+
+    let report = koncord::report(&clients);
+    assert_eq!(
+        report[0],
+        TransactionReport {
+            client: 1,
+            deposits: 2,
+            withdrawals: 1,
+            disputes: 1,
+            deposited: Decimal::new(3, 0),
+            withdrawn: Decimal::new(15, 1),
+            locked: false,
+        }
+    );
+}
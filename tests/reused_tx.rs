@@ -0,0 +1,46 @@
+use rust_decimal::Decimal;
+
+use koncord::Engine;
+
+#[test]
+fn reused_tx() {
+    let records = "\
+type,       client, tx, amount
+deposit,    1,      1,  5.0
+deposit,    1,      2,  5.0
+withdrawal, 1,      1,  2.0
+withdrawal, 1,      3,  1.0
+deposit,    1,      3,  1.0
+dispute,    1,      2,
+";
+    let transaction_records = koncord::reader_builder().from_reader(records.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    // Reused IDs are still processed, only counted.
+    assert_eq!(engine.stats().reused_tx, 2);
+    assert_eq!(engine.stats().rejected(), 0);
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::new(3, 0));
+    assert_eq!(account.held(), Decimal::new(5, 0));
+}
+
+#[test]
+fn reused_tx_distinct() {
+    let records = "\
+type,       client, tx, amount
+deposit,    1,      1,  5.0
+deposit,    1,      1,  5.0
+withdrawal, 1,      2,  2.0
+dispute,    1,      1,
+";
+    let transaction_records = koncord::reader_builder().from_reader(records.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    // Disputes share the ID of their deposit and repeats of one kind are not
+    // reuse across kinds.
+    assert_eq!(engine.stats().reused_tx, 0);
+}
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use rust_decimal::Decimal;
+
+use koncord::client::Client;
+use koncord::Engine;
+
+fn records_path() -> PathBuf {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/reversal.csv");
+    records_path
+}
+
+// Client 1 reverses deposit 1 then withdrawal 3, the dispute of the reversed
+// deposit is refused. Client 2 cannot reverse its refused withdrawal and
+// reverses deposit 4 only once.
+fn assert_reversed(clients: &HashMap<u16, Client>) {
+    let account = clients[&1].get();
+    assert_eq!(account.available(), Decimal::new(5, 0));
+    assert_eq!(account.held(), Decimal::ZERO);
+    assert_eq!(account.total(), Decimal::new(5, 0));
+    assert!(!account.is_locked());
+
+    let account = clients[&2].get();
+    assert_eq!(account.available(), Decimal::ZERO);
+    assert_eq!(account.total(), Decimal::ZERO);
+}
+
+#[test]
+fn reversal() {
+    let transaction_records = koncord::reader_builder().from_path(records_path()).unwrap();
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    assert!(engine.open_disputes().is_empty());
+    assert_reversed(&engine.into_clients());
+}
+
+#[test]
+fn reversal_run() {
+    let records_path = records_path();
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    koncord::run(
+        &mut clients,
+        transaction_records,
+        records_path.to_str().unwrap(),
+    )
+    .unwrap();
+
+    assert_reversed(&clients);
+}
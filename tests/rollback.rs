@@ -0,0 +1,71 @@
+use rust_decimal::Decimal;
+
+use koncord::Engine;
+
+const INITIAL: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  10.0
+deposit,    2,      2,  5.0
+";
+
+const BATCH: &str = "\
+type,       client, tx, amount
+deposit,    1,      3,  2.5
+withdrawal, 1,      4,  4.0
+fee,        2,      5,  0.5
+dispute,    1,      3,
+deposit,    2,      6,  3.0
+dispute,    2,      6,
+resolve,    2,      6,
+adjustment, 2,      7,  -1.0
+";
+
+#[test]
+fn rollback() {
+    let mut engine = Engine::new();
+    engine
+        .process(koncord::reader_builder().from_reader(INITIAL.as_bytes()))
+        .unwrap();
+    let initial = engine.clone();
+
+    engine
+        .process(koncord::reader_builder().from_reader(BATCH.as_bytes()))
+        .unwrap();
+    assert_eq!(engine.client(1).unwrap().get().held(), Decimal::new(25, 1));
+
+    engine
+        .rollback(koncord::reader_builder().from_reader(BATCH.as_bytes()))
+        .unwrap();
+
+    for id in [1, 2] {
+        let (client, expected) = (engine.client(id).unwrap(), initial.client(id).unwrap());
+        assert!(client.balances_equal(expected), "client {id}");
+    }
+    assert!(engine.open_disputes().is_empty());
+    assert_eq!(engine.client(1).unwrap().open_dispute_count(), 0);
+}
+
+#[test]
+fn rollback_chargeback() {
+    let records = "\
+type,       client, tx, amount
+deposit,    1,      1,  10.0
+dispute,    1,      1,
+chargeback, 1,      1,
+";
+    let mut engine = Engine::new();
+    engine
+        .process(koncord::reader_builder().from_reader(records.as_bytes()))
+        .unwrap();
+    let skipped = engine.stats().skipped;
+
+    engine
+        .rollback(koncord::reader_builder().from_reader(records.as_bytes()))
+        .unwrap();
+
+    // The locked account refuses every undo.
+    let account = engine.client(1).unwrap().get();
+    assert!(account.is_locked());
+    assert_eq!(account.total(), Decimal::ZERO);
+    assert_eq!(engine.stats().skipped, skipped + 3);
+}
@@ -0,0 +1,59 @@
+use rust_decimal::Decimal;
+
+use koncord::client::Rounding;
+use koncord::Engine;
+
+const DUST: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  10.0
+withdrawal, 1,      2,  1.00005
+deposit,    1,      3,  0.00004
+";
+
+fn available(rounding: Rounding) -> Decimal {
+    let mut engine = Engine::new().rounding(rounding);
+    engine
+        .process(koncord::reader_builder().from_reader(DUST.as_bytes()))
+        .unwrap();
+
+    engine.client(1).unwrap().get().available()
+}
+
+#[test]
+fn rounding_half_up() {
+    assert_eq!(available(Rounding::HalfUp), Decimal::new(89999, 4));
+}
+
+#[test]
+fn rounding_half_even() {
+    assert_eq!(available(Rounding::HalfEven), Decimal::new(9, 0));
+}
+
+#[test]
+fn rounding_down() {
+    assert_eq!(available(Rounding::Down), Decimal::new(9, 0));
+}
+
+#[test]
+fn rounding_exact() {
+    assert_eq!(available(Rounding::Exact), Decimal::new(899999, 5));
+}
+
+#[test]
+fn rounding_with_scale() {
+    let records = "\
+type,       client, tx, amount
+deposit,    1,      1,  10.0
+withdrawal, 1,      2,  1.005
+deposit,    1,      3,  0.004
+";
+    let mut engine = Engine::new().with_scale(2).rounding(Rounding::HalfUp);
+    engine
+        .process(koncord::reader_builder().from_reader(records.as_bytes()))
+        .unwrap();
+
+    // Amounts round to cents, the deposit rounding to zero is refused.
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::new(899, 2));
+    assert_eq!(engine.stats().zero_amounts, 1);
+}
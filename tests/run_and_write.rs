@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use koncord::client::Client;
+
+#[test]
+fn run_and_write_matches_buffered() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/toy/twenty.csv");
+
+    let mut clients: HashMap<u16, Client> = HashMap::with_capacity(usize::from(u16::MAX));
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    koncord::run(
+        &mut clients,
+        transaction_records,
+        records_path.to_str().unwrap(),
+    )
+    .unwrap();
+
+    let mut wtr = csv::Writer::from_writer(vec![]);
+    let mut clients: Vec<&Client> = clients.values().collect();
+    clients.sort();
+    for client in clients {
+        wtr.serialize(client).unwrap();
+    }
+    wtr.flush().unwrap();
+    let buffered = wtr.into_inner().unwrap();
+
+    let mut streamed = vec![];
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    koncord::run_and_write(transaction_records, &mut streamed).unwrap();
+
+    assert_eq!(
+        String::from_utf8(streamed).unwrap(),
+        String::from_utf8(buffered).unwrap()
+    );
+}
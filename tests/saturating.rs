@@ -0,0 +1,38 @@
+use rust_decimal::Decimal;
+
+use koncord::Engine;
+
+// The deposit is disputed and charged back after most of it was withdrawn.
+const OVER_SUBTRACTING: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  100.0
+withdrawal, 1,      2,  80.0
+dispute,    1,      1,
+chargeback, 1,      1,
+";
+
+#[test]
+fn saturating_clamps_at_zero() {
+    let transaction_records = koncord::reader_builder().from_reader(OVER_SUBTRACTING.as_bytes());
+
+    let mut engine = Engine::new().saturating(true);
+    engine.process(transaction_records).unwrap();
+
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::ZERO);
+    assert_eq!(account.held(), Decimal::ZERO);
+    assert_eq!(account.total(), Decimal::ZERO);
+    assert!(account.is_locked());
+}
+
+#[test]
+fn saturating_unset() {
+    let transaction_records = koncord::reader_builder().from_reader(OVER_SUBTRACTING.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    let account = engine.client(1).unwrap().get();
+    assert_eq!(account.available(), Decimal::new(-80, 0));
+    assert_eq!(account.total(), Decimal::new(-80, 0));
+}
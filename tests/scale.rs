@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use koncord::{Engine, DEFAULT_SCALE};
+
+const EXPECTED: &str = "\
+client,available,held,total,locked
+1,1.00,0.50,1.50,false
+2,1.50,0.50,2.00,false
+999,0.00,0.00,0.00,false
+1000,500.00,0.00,500.00,false
+1001,0.00,0.00,0.00,false
+";
+
+#[test]
+fn scale_two_decimals() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/complex.csv");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_koncord"))
+        .args(["--scale", "2"])
+        .arg(&records_path)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), EXPECTED);
+}
+
+#[test]
+fn scale_accessor() {
+    assert_eq!(DEFAULT_SCALE, 4);
+    assert_eq!(Engine::new().scale(), DEFAULT_SCALE);
+    assert_eq!(Engine::new().with_scale(2).scale(), 2);
+}
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use koncord::{SchemaError, TransactionKind};
+
+#[test]
+fn validate_schema() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/schema.csv");
+
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    assert_eq!(
+        koncord::validate_schema(transaction_records),
+        Err(vec![
+            SchemaError::MissingAmount {
+                line: 3,
+                kind: TransactionKind::Deposit,
+            },
+            SchemaError::NegativeAmount {
+                line: 4,
+                kind: TransactionKind::Withdrawal,
+            },
+        ])
+    );
+}
+
+#[test]
+fn validate_schema_valid() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/complex.csv");
+
+    let transaction_records = koncord::reader_builder().from_path(&records_path).unwrap();
+    assert_eq!(koncord::validate_schema(transaction_records), Ok(()));
+}
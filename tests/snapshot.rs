@@ -0,0 +1,102 @@
+use rust_decimal::Decimal;
+
+use koncord::client::{load_clients, Client, ClientSnapshot};
+use koncord::{Engine, KoncordError};
+
+const SNAPSHOTS: &str = "\
+client,available,held,total,locked
+1,1.5,0,1.5,false
+2,2,1,3,false
+3,0,0,0,true
+";
+
+#[test]
+fn snapshot_load_clients() {
+    let mut reader = koncord::reader_builder().from_reader(SNAPSHOTS.as_bytes());
+    let snapshots: Vec<ClientSnapshot> = reader.deserialize().map(Result::unwrap).collect();
+
+    let clients = load_clients(snapshots).unwrap();
+    assert_eq!(clients.len(), 3);
+
+    let client = &clients[&1];
+    assert_eq!(client.id(), 1);
+    assert_eq!(client.get().available(), Decimal::new(15, 1));
+    assert_eq!(client.get().total(), Decimal::new(15, 1));
+
+    let client = &clients[&2];
+    assert_eq!(client.get().held(), Decimal::ONE);
+    assert_eq!(client.get().total(), Decimal::new(3, 0));
+    assert!(!client.get().is_locked());
+
+    assert!(clients[&3].get().is_locked());
+    assert!(!clients[&3].balances_equal(&Client::new(3)));
+}
+
+#[test]
+fn snapshot_invalid() {
+    let snapshot = ClientSnapshot {
+        client: 7,
+        available: Decimal::ONE,
+        held: Decimal::ONE,
+        total: Decimal::ONE,
+        locked: false,
+        flagged: false,
+    };
+
+    assert_eq!(
+        load_clients([snapshot]).unwrap_err(),
+        KoncordError::InvalidSnapshot { client: 7 }
+    );
+}
+
+#[test]
+fn snapshot_overflow() {
+    // Available plus held funds overflow, no total can match them.
+    let snapshot = ClientSnapshot {
+        client: 7,
+        available: Decimal::MAX,
+        held: Decimal::MAX,
+        total: Decimal::MAX,
+        locked: false,
+        flagged: false,
+    };
+
+    assert_eq!(
+        load_clients([snapshot]).unwrap_err(),
+        KoncordError::InvalidSnapshot { client: 7 }
+    );
+}
+
+#[test]
+fn snapshot_eq_client() {
+    let records = "\
+type,       client, tx, amount
+deposit,    1,      1,  2.0
+deposit,    1,      2,  1.0
+dispute,    1,      2,
+withdrawal, 1,      3,  0.5
+";
+    let mut engine = Engine::new();
+    engine
+        .process(koncord::reader_builder().from_reader(records.as_bytes()))
+        .unwrap();
+
+    let expected = ClientSnapshot {
+        client: 1,
+        available: Decimal::new(15, 1),
+        held: Decimal::new(10000, 4),
+        total: Decimal::new(25, 1),
+        locked: false,
+        flagged: false,
+    };
+    let client = engine.client(1).unwrap();
+    assert_eq!(*client, expected);
+    assert_eq!(expected, *client);
+    assert_ne!(
+        *client,
+        ClientSnapshot {
+            locked: true,
+            ..expected
+        }
+    );
+}
@@ -0,0 +1,92 @@
+use rust_decimal::Decimal;
+
+use koncord::{Engine, KoncordError};
+
+const BASE: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  10.0
+";
+
+const BAD_BATCH: &str = "\
+type,       client, tx, amount
+deposit,    1,      2,  5.0
+withdrawal, 1,      3,  -1.0
+deposit,    2,      4,  1.0
+deposit,    2,      5,  one
+";
+
+const GOOD_BATCH: &str = "\
+type,       client, tx, amount
+deposit,    1,      2,  5.0
+withdrawal, 1,      3,  1.0
+deposit,    2,      4,  1.0
+";
+
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .process(koncord::reader_builder().from_reader(BASE.as_bytes()))
+        .unwrap();
+    engine
+}
+
+#[test]
+fn stage_rejected() {
+    let engine = engine();
+    let errors = engine
+        .stage(koncord::reader_builder().from_reader(BAD_BATCH.as_bytes()))
+        .unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec![
+            KoncordError::NegativeAmount {
+                tx: 3,
+                amount: Decimal::new(-10, 1),
+            },
+            KoncordError::InvalidAmount {
+                line: 5,
+                value: "one".to_string(),
+            },
+        ]
+    );
+    assert_eq!(engine.client(1).unwrap().get().total(), Decimal::new(10, 0));
+    assert!(!engine.contains_client(2));
+    assert_eq!(engine.stats().records, 1);
+}
+
+#[test]
+fn stage_commit() {
+    let mut engine = engine();
+    let batch = engine
+        .stage(koncord::reader_builder().from_reader(GOOD_BATCH.as_bytes()))
+        .unwrap();
+    assert_eq!(batch.stats().records, 4);
+    assert_eq!(engine.client(1).unwrap().get().total(), Decimal::new(10, 0));
+
+    engine.commit(batch).unwrap();
+
+    assert_eq!(engine.client(1).unwrap().get().total(), Decimal::new(14, 0));
+    assert_eq!(engine.client(2).unwrap().get().total(), Decimal::new(1, 0));
+}
+
+#[test]
+fn stage_commit_stale() {
+    let mut engine = engine();
+    let batch = engine
+        .stage(koncord::reader_builder().from_reader(GOOD_BATCH.as_bytes()))
+        .unwrap();
+    let records = "\
+type,       client, tx, amount
+deposit,    3,      6,  2.0
+";
+    engine
+        .process(koncord::reader_builder().from_reader(records.as_bytes()))
+        .unwrap();
+
+    assert_eq!(engine.commit(batch), Err(KoncordError::StaleBatch));
+    // The records processed since staging are kept.
+    assert_eq!(engine.client(1).unwrap().get().total(), Decimal::new(10, 0));
+    assert_eq!(engine.client(3).unwrap().get().total(), Decimal::new(2, 0));
+    assert!(!engine.contains_client(2));
+}
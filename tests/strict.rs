@@ -0,0 +1,30 @@
+use rust_decimal::Decimal;
+
+use koncord::{Engine, KoncordError};
+
+const DISPUTE_AMOUNT: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  5.0
+dispute,    1,      1,  5.0
+";
+
+#[test]
+fn strict_dispute_amount_rejected() {
+    let transaction_records = koncord::reader_builder().from_reader(DISPUTE_AMOUNT.as_bytes());
+
+    let mut engine = Engine::new().strict(true);
+    let err = engine.process(transaction_records).err().unwrap();
+    assert_eq!(
+        err.downcast_ref::<KoncordError>(),
+        Some(&KoncordError::UnexpectedAmount { tx: 1 })
+    );
+}
+
+#[test]
+fn strict_dispute_amount_ignored() {
+    let transaction_records = koncord::reader_builder().from_reader(DISPUTE_AMOUNT.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+    assert_eq!(engine.client(1).unwrap().get().held(), Decimal::new(5, 0));
+}
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use koncord::client::Client;
+use koncord::{KoncordError, Summary};
+use rust_decimal::Decimal;
+
+#[test]
+fn summary_complex() {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data/complex.csv");
+
+    let mut clients: HashMap<u16, Client> = HashMap::with_capacity(usize::from(u16::MAX));
+
+    let transaction_records = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_path(&records_path)
+        .unwrap();
+
+    koncord::run(
+        &mut clients,
+        transaction_records,
+        records_path.to_str().unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        koncord::summarize(&clients).unwrap(),
+        Summary {
+            clients: 5,
+            locked: 0,
+            available: Decimal::new(5025, 1),
+            held: Decimal::ONE,
+            total: Decimal::new(5035, 1),
+        }
+    );
+}
+
+#[test]
+fn summary_overflow() {
+    // Each balance is valid, their sum exceeds `Decimal::MAX`.
+    let mut records = String::from("type,client,tx,amount\n");
+    for client in 1..=12 {
+        records.push_str(&format!(
+            "deposit,{client},{client},7000000000000000000000000000.0\n"
+        ));
+    }
+    let mut engine = koncord::Engine::new();
+    engine
+        .process(koncord::reader_builder().from_reader(records.as_bytes()))
+        .unwrap();
+
+    assert_eq!(
+        koncord::summarize(&engine.into_clients()),
+        Err(KoncordError::SummaryOverflow)
+    );
+}
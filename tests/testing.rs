@@ -0,0 +1,21 @@
+use koncord::testing::assert_balances_eq;
+
+#[test]
+fn testing_ignores_scale() {
+    assert_balances_eq(
+        "client,available,held,total,locked\n1,1.5,0,1.5,false\n",
+        "\
+client, available, held,   total,  locked
+1,      1.5000,    0.0000, 1.5000, false
+",
+    );
+}
+
+#[test]
+#[should_panic(expected = "client balances differ")]
+fn testing_balances_differ() {
+    assert_balances_eq(
+        "client,available,held,total,locked\n1,1.5,0,1.5,false\n",
+        "client,available,held,total,locked\n1,1.5,0,1.5,true\n",
+    );
+}
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use koncord::Engine;
+
+fn records_path(file: &str) -> PathBuf {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data");
+    records_path.push(file);
+    records_path
+}
+
+fn koncord(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_koncord"))
+        .args(args)
+        .arg(records_path("complex.csv"))
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn threads_match_sequential() {
+    assert_eq!(koncord(&["--threads", "4"]), koncord(&[]));
+}
+
+#[test]
+fn run_parallel_100k_transactions() {
+    let records_path = records_path("100k_transactions.csv");
+    let write = |engine: Engine| {
+        let mut output = vec![];
+        engine.write(&mut output).unwrap();
+        output
+    };
+
+    let mut sequential = Engine::new();
+    sequential
+        .process(koncord::reader_builder().from_path(&records_path).unwrap())
+        .unwrap();
+    let parallel = koncord::run_parallel(
+        koncord::reader_builder().from_path(&records_path).unwrap(),
+        4,
+        Engine::new,
+    )
+    .unwrap();
+
+    assert_eq!(parallel.stats(), sequential.stats());
+    assert_eq!(write(parallel), write(sequential));
+}
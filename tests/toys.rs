@@ -1,7 +1,6 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-use koncord;
 use koncord::client::Client;
 
 const BASE_EXPECTED: &str = "\
@@ -40,8 +39,7 @@ fn toy_deposit_withdraw() {
     let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     records_path.push("tests/data/toy/base.csv");
 
-    let mut clients: HashMap<u16, Client> =
-        HashMap::with_capacity(usize::try_from(u16::MAX).unwrap());
+    let mut clients: HashMap<u16, Client> = HashMap::with_capacity(usize::from(u16::MAX));
 
     let transaction_records = csv::ReaderBuilder::new()
         .trim(csv::Trim::All)
@@ -74,8 +72,7 @@ fn toy_dispute() {
     let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     records_path.push("tests/data/toy/dispute.csv");
 
-    let mut clients: HashMap<u16, Client> =
-        HashMap::with_capacity(usize::try_from(u16::MAX).unwrap());
+    let mut clients: HashMap<u16, Client> = HashMap::with_capacity(usize::from(u16::MAX));
 
     let transaction_records = csv::ReaderBuilder::new()
         .trim(csv::Trim::All)
@@ -107,8 +104,7 @@ fn toy_dispute() {
 fn toy_resolve() {
     let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     records_path.push("tests/data/toy/resolve.csv");
-    let mut clients: HashMap<u16, Client> =
-        HashMap::with_capacity(usize::try_from(u16::MAX).unwrap());
+    let mut clients: HashMap<u16, Client> = HashMap::with_capacity(usize::from(u16::MAX));
 
     let transaction_records = csv::ReaderBuilder::new()
         .trim(csv::Trim::All)
@@ -140,8 +136,7 @@ fn toy_resolve() {
 fn toy_chargeback() {
     let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     records_path.push("tests/data/toy/chargeback.csv");
-    let mut clients: HashMap<u16, Client> =
-        HashMap::with_capacity(usize::try_from(u16::MAX).unwrap());
+    let mut clients: HashMap<u16, Client> = HashMap::with_capacity(usize::from(u16::MAX));
 
     let transaction_records = csv::ReaderBuilder::new()
         .trim(csv::Trim::All)
@@ -174,8 +169,7 @@ fn toy_twenty() {
     let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     records_path.push("tests/data/toy/twenty.csv");
 
-    let mut clients: HashMap<u16, Client> =
-        HashMap::with_capacity(usize::try_from(u16::MAX).unwrap());
+    let mut clients: HashMap<u16, Client> = HashMap::with_capacity(usize::from(u16::MAX));
 
     let transaction_records = csv::ReaderBuilder::new()
         .trim(csv::Trim::All)
@@ -1,7 +1,6 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-use koncord;
 use koncord::client::Client;
 
 #[test]
@@ -11,8 +10,7 @@ fn stress_100k_transactions() {
     let mut stress_test = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     stress_test.push("tests/data/100k_transactions.csv");
 
-    let mut clients: HashMap<u16, Client> =
-        HashMap::with_capacity(usize::try_from(u16::MAX).unwrap());
+    let mut clients: HashMap<u16, Client> = HashMap::with_capacity(usize::from(u16::MAX));
 
     let transaction_records = csv::ReaderBuilder::new()
         .trim(csv::Trim::All)
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use rust_decimal::Decimal;
+
+use koncord::client::Client;
+use koncord::{Engine, Record, TransactionKind};
+
+const UNKNOWN_KIND: &str = "\
+type,     client, tx, amount
+deposit,  1,      1,  2.0
+refund,   1,      2,  1.0
+deposit,  1,      3,  1.0
+transfer, 2,      4,  1.0
+";
+
+#[test]
+fn unknown_kind_skipped() {
+    let transaction_records = koncord::reader_builder().from_reader(UNKNOWN_KIND.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    assert_eq!(engine.client(1).unwrap().get().total(), Decimal::new(3, 0));
+    assert!(!engine.contains_client(2));
+    assert_eq!(engine.stats().applied, 2);
+    assert_eq!(engine.stats().unknown_kinds, 2);
+}
+
+#[test]
+fn unknown_kind_skipped_run() {
+    let transaction_records =
+        koncord::reader_builder().from_reader(Cursor::new(UNKNOWN_KIND.as_bytes()));
+
+    let mut clients: HashMap<u16, Client> = HashMap::new();
+    koncord::run(&mut clients, transaction_records, "").unwrap();
+
+    assert_eq!(clients[&1].get().total(), Decimal::new(3, 0));
+    assert_eq!(clients.len(), 1);
+}
+
+#[test]
+fn unknown_kind_round_trip() {
+    let mut rdr = koncord::reader_builder().from_reader(UNKNOWN_KIND.as_bytes());
+    let record: Record = rdr.deserialize().nth(1).unwrap().unwrap();
+    assert_eq!(
+        record.kind(),
+        &TransactionKind::Unknown("refund".to_string())
+    );
+
+    let mut wtr = csv::Writer::from_writer(vec![]);
+    wtr.serialize(&record).unwrap();
+    assert_eq!(
+        String::from_utf8(wtr.into_inner().unwrap()).unwrap(),
+        "type,client,tx,amount,description,timestamp\nrefund,1,2,1,,\n"
+    );
+}
@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+fn koncord(file: &str) -> std::process::Output {
+    let mut records_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    records_path.push("tests/data");
+    records_path.push(file);
+
+    Command::new(env!("CARGO_BIN_EXE_koncord"))
+        .arg("--validate-only")
+        .arg(&records_path)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn validate_only_invalid() {
+    let output = koncord("invalid_rows.csv");
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "\
+Missing transaction ID on line 3
+Negative amount -1 for transaction 3
+Invalid amount \"inf\" on line 5
+"
+    );
+}
+
+#[test]
+fn validate_only_valid() {
+    let output = koncord("complex.csv");
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
@@ -0,0 +1,33 @@
+use rust_decimal::Decimal;
+
+use koncord::Engine;
+
+const ZERO_AMOUNT: &str = "\
+type,       client, tx, amount
+deposit,    1,      1,  1.0
+deposit,    1,      2,  0.0
+";
+
+#[test]
+fn zero_amount_refused() {
+    let transaction_records = koncord::reader_builder().from_reader(ZERO_AMOUNT.as_bytes());
+
+    let mut engine = Engine::new();
+    engine.process(transaction_records).unwrap();
+
+    assert_eq!(engine.client(1).unwrap().get().total(), Decimal::ONE);
+    assert_eq!(engine.stats().applied, 1);
+    assert_eq!(engine.stats().zero_amounts, 1);
+}
+
+#[test]
+fn zero_amount_allowed() {
+    let transaction_records = koncord::reader_builder().from_reader(ZERO_AMOUNT.as_bytes());
+
+    let mut engine = Engine::new().allow_zero_amount(true);
+    engine.process(transaction_records).unwrap();
+
+    assert_eq!(engine.client(1).unwrap().get().total(), Decimal::ONE);
+    assert_eq!(engine.stats().applied, 2);
+    assert_eq!(engine.stats().zero_amounts, 0);
+}